@@ -0,0 +1,96 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Implements `RemoteStruct` for a struct whose fields are annotated with the
+/// SDK path of the member they mirror.
+///
+/// ## Example:
+/// ```
+/// #[derive(RemoteStruct)]
+/// struct CrewView {
+///     #[sdk("Crew.CrewId")]
+///     crew_id: Guid,
+/// }
+/// ```
+#[proc_macro_derive(RemoteStruct, attributes(sdk))]
+pub fn derive_remote_struct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(v) => v.into(),
+        Err(v) => v.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "RemoteStruct cannot be derived for generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(v) => match &v.fields {
+            Fields::Named(v) => &v.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    name,
+                    "RemoteStruct needs a struct with named fields",
+                ))
+            }
+        },
+        _ => return Err(Error::new_spanned(name, "RemoteStruct needs a struct")),
+    };
+
+    let mut field_names = vec![];
+    let mut field_types = vec![];
+    let mut paths = vec![];
+    for field in fields {
+        let attribute = field
+            .attrs
+            .iter()
+            .find(|v| v.path().is_ident("sdk"))
+            .ok_or_else(|| {
+                Error::new_spanned(field, "expected #[sdk(\"Class.Member\")] on every field")
+            })?;
+        let path = attribute.parse_args::<LitStr>()?;
+        if !path.value().contains('.') {
+            return Err(Error::new_spanned(
+                path,
+                "expected at least one '.' in attribute_path",
+            ));
+        }
+        field_names.push(field.ident.clone().unwrap());
+        field_types.push(&field.ty);
+        paths.push(path);
+    }
+    let indices = 0..field_names.len();
+
+    Ok(quote! {
+        impl crate::structs::remote_struct::RemoteStruct for #name {
+            fn layout(
+                sdk: &crate::services::sdk::SdkHandle,
+            ) -> ::std::result::Result<
+                ::std::sync::Arc<crate::structs::remote_struct::RemoteLayout>,
+                crate::core::reader::MemoryReaderError,
+            > {
+                static LAYOUT: crate::structs::remote_struct::RemoteLayoutCache =
+                    crate::structs::remote_struct::RemoteLayoutCache::new(
+                        &[#(#paths),*],
+                        &[#(::std::mem::size_of::<#field_types>()),*],
+                    );
+                LAYOUT.get(sdk)
+            }
+
+            fn from_block(
+                layout: &crate::structs::remote_struct::RemoteLayout,
+                block: &[u8],
+            ) -> Self {
+                Self {
+                    #(#field_names: layout.field::<#field_types>(block, #indices),)*
+                }
+            }
+        }
+    })
+}
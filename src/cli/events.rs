@@ -0,0 +1,24 @@
+use crate::core::reader::ProcessTarget;
+use crate::services::reader_thread::ReaderThread;
+use crate::services::sdk::SdkHandle;
+use crate::services::world_reader::WorldReader;
+
+/// Prints every world event as one JSON line until the process or the reader
+/// is stopped.
+pub fn run(
+    target: ProcessTarget,
+    preferred_pid: Option<u32>,
+    tick_rate: f64,
+    max_array_count: u32,
+) {
+    let mut reader = WorldReader::new(target, SdkHandle::load_default(), max_array_count);
+    reader.prefer_pid(preferred_pid);
+    let events = reader.subscribe();
+    let reader = ReaderThread::spawn(reader, tick_rate);
+    for event in events.iter() {
+        println!("{}", serde_json::to_string(&event).unwrap());
+    }
+    if let Some(reason) = reader.stopped() {
+        eprintln!("Reader stopped: {}", reason);
+    }
+}
@@ -0,0 +1,39 @@
+use crate::core::context::ReaderContext;
+use crate::core::reader::ProcessTarget;
+use crate::services::sdk::SdkHandle;
+use crate::structs::remote_ptr::RemotePtr;
+
+const USAGE: &str = "Usage:
+    sot-framework inspect <address> <Class> [--depth <n>]
+
+Reads the object at <address> (hex) in the running game using the SDK layout
+of <Class> and prints it as JSON. Object pointers are followed <n> levels deep
+(0 by default).";
+
+pub fn run(args: &[String], target: &ProcessTarget, max_array_count: u32) {
+    let (address, class_name) = match args {
+        [address, class_name, ..] => (address, class_name),
+        _ => return println!("{}", USAGE),
+    };
+    let address = match usize::from_str_radix(address.trim_start_matches("0x"), 16) {
+        Ok(v) => v,
+        Err(_) => return println!("Invalid address \"{}\"", address),
+    };
+    let depth = match args.iter().position(|v| v == "--depth") {
+        Some(i) => match args.get(i + 1).map(|v| v.parse::<u32>()) {
+            Some(Ok(v)) => v,
+            _ => return println!("{}", USAGE),
+        },
+        None => 0,
+    };
+
+    let mut ctx = match ReaderContext::attach(target, SdkHandle::load_default()) {
+        Ok(v) => v,
+        Err(v) => return println!("Could not attach to {}: {:?}", target, v),
+    };
+    ctx.set_max_array_count(max_array_count);
+    match ctx.read_object_dynamic(RemotePtr::new(address), class_name, depth) {
+        Ok(v) => println!("{}", serde_json::to_string_pretty(&v).unwrap()),
+        Err(v) => println!("Could not read {}: {:?}", class_name, v),
+    }
+}
@@ -0,0 +1,91 @@
+pub mod events;
+pub mod inspect;
+pub mod sdk;
+pub mod snapshot;
+pub mod target;
+
+use crate::core::reader::{ProcessTarget, DEFAULT_MAX_ARRAY_COUNT};
+
+const USAGE: &str = "Usage:
+    sot-framework                  Start the reader
+    sot-framework sdk <command>    Inspect JSON-SDK dumps (sot-framework sdk help)
+    sot-framework inspect <address> <Class> [--depth <n>]
+                                   Dump a live object as JSON
+    sot-framework snapshot [--out <file>]
+                                   Dump one read of the game as JSON
+    sot-framework events           Stream what changes in the game as JSON lines
+
+Options:
+    --pid <pid>         Read the process with this pid
+    --process <name>    Read the process with this name (SoTGame.exe by default)
+    --tick-rate <hz>    Reads of the game per second (30 by default)
+    --max-array-count <n>
+                        Longest game array read, longer ones are treated as
+                        garbage (65536 by default)";
+
+/// Reads of the game per second when `--tick-rate` is not given.
+pub const DEFAULT_TICK_RATE: f64 = 30.0;
+
+/// Runs the command described by `args` (program name and global options
+/// excluded).
+pub fn run(args: &[String], target: ProcessTarget, tick_rate: f64, max_array_count: u32) {
+    match args.first().map(|v| v.as_str()) {
+        Some("sdk") => sdk::run(&args[1..]),
+        Some("inspect") => {
+            // Attaches once, so there is no restart to follow.
+            let target = match target::select_process(&target) {
+                Some(pid) => ProcessTarget::Pid(pid),
+                None => target,
+            };
+            inspect::run(&args[1..], &target, max_array_count)
+        }
+        Some("snapshot") => {
+            let pid = target::select_process(&target);
+            snapshot::run(&args[1..], target, pid, max_array_count)
+        }
+        Some("events") => {
+            let pid = target::select_process(&target);
+            events::run(target, pid, tick_rate, max_array_count)
+        }
+        _ => println!("{}", USAGE),
+    }
+}
+
+/// Removes `--tick-rate <hz>` from `args` and returns the tick rate.
+pub fn parse_tick_rate(args: &[String]) -> Result<(f64, Vec<String>), String> {
+    let mut tick_rate = DEFAULT_TICK_RATE;
+    let mut rest = vec![];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--tick-rate" => {
+                let value = iter.next().ok_or("--tick-rate expects a number")?;
+                tick_rate = match value.parse::<f64>() {
+                    Ok(v) if v > 0.0 && v.is_finite() => v,
+                    _ => return Err(format!("Invalid tick rate \"{}\"", value)),
+                };
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    Ok((tick_rate, rest))
+}
+
+/// Removes `--max-array-count <n>` from `args` and returns the limit.
+pub fn parse_max_array_count(args: &[String]) -> Result<(u32, Vec<String>), String> {
+    let mut max_array_count = DEFAULT_MAX_ARRAY_COUNT;
+    let mut rest = vec![];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--max-array-count" => {
+                let value = iter.next().ok_or("--max-array-count expects a number")?;
+                max_array_count = value
+                    .parse::<u32>()
+                    .map_err(|_| format!("Invalid array count \"{}\"", value))?;
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    Ok((max_array_count, rest))
+}
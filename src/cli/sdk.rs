@@ -0,0 +1,283 @@
+use std::fs;
+
+use regex::RegexBuilder;
+
+use crate::services::sdk::{default_sdk_path, SdkService};
+use crate::services::sdk_diff::SdkDiff;
+use crate::services::sdk_export::{export_cpp, export_rust};
+
+const USAGE: &str = "Usage:
+    sot-framework sdk class <Class>                 Flattened layout of a class or struct
+    sot-framework sdk offset <Class.Member>         Offset, size and type of a member
+    sot-framework sdk search <pattern>              Search class and member names (regex)
+    sot-framework sdk tree <Class>                  Inheritance tree of a class
+    sot-framework sdk info                          SDK metadata and number of classes
+    sot-framework sdk normalize                     Remove metadata keys from the SDK files
+    sot-framework sdk diff <old dir> <new dir> [--json]
+    sot-framework sdk export <rust|cpp> <Class>... [--out <file>]
+
+Options:
+    --sdk <dir>    JSON-SDK directory to read instead of the default one
+    --out <file>   Write the output to a file instead of stdout";
+
+pub fn run(args: &[String]) {
+    match args.first().map(|v| v.as_str()) {
+        Some("class") => class(&args[1..]),
+        Some("offset") => offset(&args[1..]),
+        Some("search") => search(&args[1..]),
+        Some("tree") => tree(&args[1..]),
+        Some("info") => info(&args[1..]),
+        Some("normalize") => normalize(&args[1..]),
+        Some("diff") => diff(&args[1..]),
+        Some("export") => export(&args[1..]),
+        _ => println!("{}", USAGE),
+    }
+}
+
+/// Arguments that are neither flags nor flag values.
+fn positional(args: &[String]) -> Vec<&String> {
+    let mut positional = vec![];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--sdk" || arg == "--out" {
+            iter.next();
+        } else if !arg.starts_with("--") {
+            positional.push(arg);
+        }
+    }
+    positional
+}
+
+fn sdk_path(args: &[String]) -> &str {
+    match args.iter().position(|v| v == "--sdk") {
+        Some(i) if i + 1 < args.len() => &args[i + 1],
+        _ => default_sdk_path(),
+    }
+}
+
+/// The default SDK is loaded with its overrides, like the reader does.
+fn load_sdk(args: &[String]) -> SdkService {
+    if args.iter().any(|v| v == "--sdk") {
+        return SdkService::from_dir(sdk_path(args));
+    }
+    let mut sdk_service = SdkService::new();
+    sdk_service.scan_sdk();
+    sdk_service
+}
+
+fn class(args: &[String]) {
+    let name = match positional(args).as_slice() {
+        [name] => name.as_str(),
+        _ => return println!("{}", USAGE),
+    };
+    let sdk = load_sdk(args);
+    let mut attributes = match sdk.get_flattened_attributes(name) {
+        Some(v) => v,
+        None => return println!("Class or Struct \"{}\" does not exist", name),
+    };
+    attributes.sort_by_key(|(_, v)| v.Offset);
+
+    match sdk.get_super(name) {
+        Some(super_name) => println!(
+            "{} : {} (size 0x{:X})",
+            name,
+            super_name,
+            sdk.get_class_or_struct_size(name)
+        ),
+        None => println!("{} (size 0x{:X})", name, sdk.get_class_or_struct_size(name)),
+    }
+    for (owner, attribute) in attributes {
+        let bit_mask = match attribute.BitMask {
+            Some(v) => format!(" (bit mask 0x{:02X})", v),
+            None => String::new(),
+        };
+        println!(
+            "    0x{:04X}  0x{:04X}  {:<40} {:<40} {}{}",
+            attribute.Offset, attribute.Size, attribute.Type, attribute.Name, owner, bit_mask
+        );
+    }
+}
+
+fn offset(args: &[String]) {
+    let attribute_path = match positional(args).as_slice() {
+        [attribute_path] => attribute_path.as_str(),
+        _ => return println!("{}", USAGE),
+    };
+    let (name, attribute_name) = match attribute_path.split_once('.') {
+        Some(v) => v,
+        None => return println!("Expected at least one '.' in attribute_path"),
+    };
+    let sdk = load_sdk(args);
+    let attributes = match sdk.get_flattened_attributes(name) {
+        Some(v) => v,
+        None => return println!("Class or Struct \"{}\" does not exist", name),
+    };
+
+    match attributes.iter().find(|(_, v)| v.Name == attribute_name) {
+        Some((owner, attribute)) => println!(
+            "{}.{}: offset 0x{:X} ({}), size 0x{:X}, type {}{}",
+            owner,
+            attribute.Name,
+            attribute.Offset,
+            attribute.Offset,
+            attribute.Size,
+            attribute.Type,
+            match attribute.BitMask {
+                Some(v) => format!(", bit mask 0x{:02X}", v),
+                None => String::new(),
+            }
+        ),
+        None => println!(
+            "Class or Struct attribute \"{}\" does not exist",
+            attribute_name
+        ),
+    }
+}
+
+fn search(args: &[String]) {
+    let pattern = match positional(args).as_slice() {
+        [pattern] => pattern.as_str(),
+        _ => return println!("{}", USAGE),
+    };
+    let regex = match RegexBuilder::new(pattern).case_insensitive(true).build() {
+        Ok(v) => v,
+        Err(v) => return println!("Invalid pattern: {}", v),
+    };
+    let sdk = load_sdk(args);
+
+    let mut names = sdk
+        .classes
+        .iter()
+        .map(|(name, class)| (name, &class.Attributes))
+        .chain(
+            sdk.structs
+                .iter()
+                .map(|(name, struct_)| (name, &struct_.Attributes)),
+        )
+        .collect::<Vec<_>>();
+    names.sort_by_key(|(name, _)| *name);
+
+    for (name, attributes) in names {
+        if regex.is_match(name) {
+            println!("{}", name);
+        }
+        for attribute in attributes {
+            if regex.is_match(&attribute.Name) {
+                println!(
+                    "{}.{}  (0x{:X}, {})",
+                    name, attribute.Name, attribute.Offset, attribute.Type
+                );
+            }
+        }
+    }
+}
+
+fn tree(args: &[String]) {
+    let name = match positional(args).as_slice() {
+        [name] => name.as_str(),
+        _ => return println!("{}", USAGE),
+    };
+    let sdk = load_sdk(args);
+    if !sdk.classes.contains_key(name) {
+        return println!("Class \"{}\" does not exist", name);
+    }
+
+    let mut ancestors = vec![];
+    let mut current = name;
+    while let Some(super_name) = sdk.get_super(current) {
+        if ancestors.contains(&super_name) {
+            break;
+        }
+        ancestors.push(super_name);
+        current = super_name;
+    }
+
+    let mut depth = 0;
+    for ancestor in ancestors.iter().rev() {
+        println!("{}{}", "    ".repeat(depth), ancestor);
+        depth += 1;
+    }
+    print_subtree(&sdk, name, depth);
+}
+
+fn print_subtree(sdk: &SdkService, name: &str, depth: usize) {
+    println!("{}{}", "    ".repeat(depth), name);
+    for subclass in sdk.get_subclasses(name) {
+        print_subtree(sdk, subclass, depth + 1);
+    }
+}
+
+fn info(args: &[String]) {
+    let sdk = load_sdk(args);
+    let mut metadata = sdk.metadata.iter().collect::<Vec<_>>();
+    metadata.sort();
+    for (key, value) in metadata {
+        println!("{}: {}", key, value);
+    }
+    println!("Classes: {}", sdk.classes.len());
+    println!("Structs: {}", sdk.structs.len());
+}
+
+fn normalize(args: &[String]) {
+    match SdkService::normalize_dir(sdk_path(args)) {
+        Ok(removed) if removed.is_empty() => println!("Nothing to normalize"),
+        Ok(removed) => {
+            for (path, key) in removed {
+                println!("Removed \"{}\" from {}", key, path.display());
+            }
+        }
+        Err(v) => println!("Could not normalize {}: {}", sdk_path(args), v),
+    }
+}
+
+fn diff(args: &[String]) {
+    let json = args.iter().any(|v| v == "--json");
+    let (old_path, new_path) = match positional(args).as_slice() {
+        [old_path, new_path] => (*old_path, *new_path),
+        _ => return println!("{}", USAGE),
+    };
+
+    let (old_sdk, new_sdk) = match (
+        SdkService::try_from_dir_uncached(old_path),
+        SdkService::try_from_dir_uncached(new_path),
+    ) {
+        (Ok(old_sdk), Ok(new_sdk)) => (old_sdk, new_sdk),
+        (Err(v), _) | (_, Err(v)) => return println!("Could not load SDK: {}", v),
+    };
+    let diff = SdkDiff::new(&old_sdk, &new_sdk);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff).unwrap());
+    } else {
+        print!("{}", diff);
+    }
+}
+
+fn export(args: &[String]) {
+    let positional = positional(args);
+    let (language, names) = match positional.split_first() {
+        Some((language, names)) if !names.is_empty() => (language.as_str(), names),
+        _ => return println!("{}", USAGE),
+    };
+    let names = names.iter().map(|v| v.as_str()).collect::<Vec<&str>>();
+    let sdk = load_sdk(args);
+
+    let output = match language {
+        "rust" => export_rust(&sdk, &names),
+        "cpp" => export_cpp(&sdk, &names),
+        _ => return println!("{}", USAGE),
+    };
+    let output = match output {
+        Ok(v) => v,
+        Err(v) => return println!("{}", v),
+    };
+
+    match args.iter().position(|v| v == "--out") {
+        Some(i) if i + 1 < args.len() => {
+            if let Err(v) = fs::write(&args[i + 1], output) {
+                println!("Could not write {}: {}", args[i + 1], v);
+            }
+        }
+        _ => print!("{}", output),
+    }
+}
@@ -0,0 +1,38 @@
+use std::fs;
+
+use crate::core::reader::ProcessTarget;
+use crate::services::sdk::SdkHandle;
+use crate::services::world_reader::WorldReader;
+
+const USAGE: &str = "Usage:
+    sot-framework snapshot [--out <file>]
+
+Reads the running game once and prints the resulting world snapshot as JSON,
+or writes it to <file>.";
+
+pub fn run(
+    args: &[String],
+    target: ProcessTarget,
+    preferred_pid: Option<u32>,
+    max_array_count: u32,
+) {
+    let out = match args.iter().position(|v| v == "--out") {
+        Some(i) => match args.get(i + 1) {
+            Some(v) => Some(v),
+            None => return println!("{}", USAGE),
+        },
+        None => None,
+    };
+
+    let mut reader = WorldReader::new(target, SdkHandle::load_default(), max_array_count);
+    reader.prefer_pid(preferred_pid);
+    let output = serde_json::to_string_pretty(&reader.tick()).unwrap();
+    match out {
+        Some(path) => {
+            if let Err(v) = fs::write(path, output) {
+                eprintln!("Could not write {}: {}", path, v);
+            }
+        }
+        None => println!("{}", output),
+    }
+}
@@ -0,0 +1,58 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use crate::core::reader::{find_processes, ProcessTarget};
+
+/// Removes `--pid <pid>` and `--process <name>` from `args` and returns the
+/// target they describe, the game by default.
+pub fn parse_target(args: &[String]) -> Result<(ProcessTarget, Vec<String>), String> {
+    let mut target = ProcessTarget::default();
+    let mut rest = vec![];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--pid" => {
+                let pid = iter.next().ok_or("--pid expects a pid")?;
+                target = ProcessTarget::Pid(
+                    pid.parse()
+                        .map_err(|_| format!("Invalid pid \"{}\"", pid))?,
+                );
+            }
+            "--process" => {
+                let name = iter.next().ok_or("--process expects a process name")?;
+                target = ProcessTarget::Name(name.clone());
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    Ok((target, rest))
+}
+
+/// When several processes match `target`, asks which one to read and returns
+/// its pid. `None` when there is nothing to choose or no terminal to ask on.
+///
+/// Only the first attach should use the pid: keeping `target` lets the reader
+/// attach again by name once the game restarts.
+pub fn select_process(target: &ProcessTarget) -> Option<u32> {
+    let processes = find_processes(target);
+    if processes.len() < 2 || !io::stdin().is_terminal() {
+        return None;
+    }
+
+    eprintln!("Several {} processes are running:", target);
+    for (index, (pid, name)) in processes.iter().enumerate() {
+        eprintln!("    {}) {} (pid {})", index + 1, name, pid);
+    }
+    loop {
+        eprint!("Select a process [1-{}]: ", processes.len());
+        io::stderr().flush().unwrap();
+
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line).unwrap_or(0) == 0 {
+            return None;
+        }
+        match line.trim().parse::<usize>() {
+            Ok(v) if v >= 1 && v <= processes.len() => return Some(processes[v - 1].0),
+            _ => eprintln!("Invalid selection \"{}\"", line.trim()),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::str::from_utf8;
+use std::sync::RwLock;
+
+use crate::core::reader::{
+    open_process, read_bytes, GameGlobals, MemoryReaderError, MemorySource, ProcessTarget,
+    DEFAULT_MAX_ARRAY_COUNT,
+};
+use crate::services::sdk::SdkHandle;
+
+/// Everything needed to read one game instance: its memory, its engine
+/// globals, the names read so far and the SDK describing its types. Contexts
+/// are independent, so a live game and a snapshot of it can be read side by
+/// side.
+pub struct ReaderContext {
+    memory: Box<dyn MemorySource + Send + Sync>,
+    pid: Option<u32>,
+    globals: GameGlobals,
+    sdk: SdkHandle,
+    max_array_count: u32,
+    /// Names by FName index, which never change while the game runs.
+    names: RwLock<HashMap<u32, String>>,
+}
+
+impl ReaderContext {
+    pub fn new(
+        memory: Box<dyn MemorySource + Send + Sync>,
+        globals: GameGlobals,
+        sdk: SdkHandle,
+    ) -> Self {
+        Self {
+            memory,
+            pid: None,
+            globals,
+            sdk,
+            max_array_count: DEFAULT_MAX_ARRAY_COUNT,
+            names: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Attaches to `target` and resolves the signatures. Fails while the game
+    /// is still starting and its module is not mapped yet, or when several
+    /// processes match `target`.
+    pub fn attach(target: &ProcessTarget, sdk: SdkHandle) -> Result<Self, MemoryReaderError> {
+        let (memory, globals) = open_process(target)?;
+        let pid = memory.pid();
+        let mut ctx = Self::new(Box::new(memory), globals, sdk);
+        ctx.pid = Some(pid);
+        Ok(ctx)
+    }
+
+    /// Pid of the process read, `None` when the memory does not come from one.
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    /// See `MemorySource::max_array_count`.
+    pub fn set_max_array_count(&mut self, max_array_count: u32) {
+        self.max_array_count = max_array_count;
+    }
+
+    pub fn globals(&self) -> &GameGlobals {
+        &self.globals
+    }
+
+    pub fn sdk(&self) -> &SdkHandle {
+        &self.sdk
+    }
+
+    pub fn read_string_default_size(&self, address: usize) -> Result<String, MemoryReaderError> {
+        self.read_string(address, 124)
+    }
+
+    pub fn read_string(&self, address: usize, size: usize) -> Result<String, MemoryReaderError> {
+        let buffer = read_bytes(self, address, size)?;
+
+        let i = match buffer.iter().position(|r| r == &b'\x00') {
+            Some(v) => v,
+            None => buffer.len(),
+        };
+
+        let result = from_utf8(&buffer[0..i]);
+
+        match result {
+            Ok(v) => Ok(String::from(v)),
+            Err(_) => Ok(self.read_name_string(address, size)?),
+        }
+    }
+
+    pub fn read_name_string(
+        &self,
+        address: usize,
+        size: usize,
+    ) -> Result<String, MemoryReaderError> {
+        let target_buffer = read_bytes(self, address, size)?;
+
+        let i = target_buffer
+            .windows(3)
+            .position(|window| window == b"\x00\x00\x00")
+            .ok_or(MemoryReaderError::ByteToStringConversion)?;
+
+        let u16_buffer: Vec<u16> = target_buffer[0..i]
+            .chunks_exact(2)
+            .map(|a| u16::from_ne_bytes([a[0], a[1]]))
+            .collect();
+
+        match String::from_utf16(&u16_buffer) {
+            Ok(v) => Ok(v),
+            Err(_) => Err(MemoryReaderError::ByteToStringConversion),
+        }
+    }
+
+    pub fn read_gname(&self, actor_id: u32) -> Result<String, MemoryReaderError> {
+        if let Some(name) = self.names.read().unwrap().get(&actor_id) {
+            return Ok(name.clone());
+        }
+        let index = u64::from(actor_id);
+        let name_ptr = self.read::<u64>((self.globals.g_names + index / 0x4000 * 0x8) as usize)?;
+        let name = self.read::<u64>((name_ptr + 0x8 * (index % 0x4000)) as usize)?;
+        let name = self.read_string((name + 0x10) as usize, 64)?;
+        self.names.write().unwrap().insert(actor_id, name.clone());
+        Ok(name)
+    }
+
+    /// Reads a `bool` member, applying its bit mask when it is part of a bitfield.
+    ///
+    /// ## Example:
+    /// ```
+    /// ctx.read_bool_field(actor.base_address.address(), "Actor.bHidden")
+    /// ```
+    pub fn read_bool_field(
+        &self,
+        address: usize,
+        attribute_path: &str,
+    ) -> Result<bool, MemoryReaderError> {
+        let sdk = self.sdk.get();
+        let attribute = sdk.get_attribute(attribute_path).ok_or_else(|| {
+            MemoryReaderError::UnknownType(format!(
+                "Class or Struct attribute \"{}\" does not exist",
+                attribute_path
+            ))
+        })?;
+        let byte = self.read::<u8>(address + attribute.Offset as usize)?;
+        Ok(attribute.bool_from_byte(byte))
+    }
+}
+
+impl MemorySource for ReaderContext {
+    fn read_into(&self, address: usize, buffer: &mut [u8]) -> Result<(), MemoryReaderError> {
+        self.memory.read_into(address, buffer)
+    }
+
+    fn max_array_count(&self) -> u32 {
+        self.max_array_count
+    }
+}
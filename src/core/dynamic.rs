@@ -0,0 +1,366 @@
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::{
+    core::{
+        context::ReaderContext,
+        reader::{read_array_header, read_bytes, read_fstring, read_ftext, MemoryReaderError},
+    },
+    services::{sdk::SdkService, sdk_export::primitive_type},
+    structs::{
+        pod::{self, Pod},
+        remote_ptr::RemotePtr,
+        unreal::UObject,
+    },
+};
+
+/// Value read using only the SDK layout of its type, see `read_object_dynamic`.
+/// Serializes like a `serde_json::Value`.
+#[derive(Debug, Clone)]
+pub enum DynamicValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    /// `FName`, `FString` and `FText`.
+    String(String),
+    Array(Vec<DynamicValue>),
+    /// Members sorted by offset.
+    Struct(Vec<(String, DynamicValue)>),
+    /// Reference to another object, followed while the depth allows it.
+    Pointer {
+        address: RemotePtr<UObject>,
+        class: Option<String>,
+        object: Option<Box<DynamicValue>>,
+    },
+    /// Type we do not know how to decode, kept as raw bytes.
+    Unknown {
+        type_: String,
+        bytes: Vec<u8>,
+    },
+    /// Member that could not be read, the rest of the object still is.
+    Error(String),
+}
+
+impl Serialize for DynamicValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            DynamicValue::Null => serializer.serialize_unit(),
+            DynamicValue::Bool(v) => serializer.serialize_bool(*v),
+            DynamicValue::Int(v) => serializer.serialize_i64(*v),
+            DynamicValue::UInt(v) => serializer.serialize_u64(*v),
+            DynamicValue::Float(v) => serializer.serialize_f64(*v),
+            DynamicValue::String(v) => serializer.serialize_str(v),
+            DynamicValue::Array(v) => v.serialize(serializer),
+            DynamicValue::Struct(members) => {
+                let mut map = serializer.serialize_map(Some(members.len()))?;
+                for (name, value) in members {
+                    map.serialize_entry(name, value)?;
+                }
+                map.end()
+            }
+            DynamicValue::Pointer {
+                address,
+                class,
+                object,
+            } => {
+                let mut map = serializer.serialize_map(None)?;
+                map.serialize_entry("Address", &format!("0x{:X}", address.address()))?;
+                map.serialize_entry("Class", class)?;
+                if let Some(object) = object {
+                    map.serialize_entry("Object", object)?;
+                }
+                map.end()
+            }
+            DynamicValue::Unknown { type_, bytes } => {
+                let hex = bytes
+                    .iter()
+                    .map(|v| format!("{:02X}", v))
+                    .collect::<String>();
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("Type", type_)?;
+                map.serialize_entry("Bytes", &hex)?;
+                map.end()
+            }
+            DynamicValue::Error(v) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("Error", v)?;
+                map.end()
+            }
+        }
+    }
+}
+
+/// Returns `Inner` for `Outer<Inner>`.
+fn generic_argument<'a>(type_name: &'a str, outer: &str) -> Option<&'a str> {
+    type_name
+        .strip_prefix(outer)?
+        .strip_prefix('<')?
+        .strip_suffix('>')
+        .map(|v| v.trim())
+}
+
+fn read_unaligned<T: Pod>(bytes: &[u8]) -> Option<T> {
+    if bytes.len() < std::mem::size_of::<T>() {
+        return None;
+    }
+    Some(pod::read_unaligned(bytes))
+}
+
+fn decode_primitive(rust_type: &str, bytes: &[u8]) -> Option<DynamicValue> {
+    Some(match rust_type {
+        "i8" => DynamicValue::Int(read_unaligned::<i8>(bytes)? as i64),
+        "i16" => DynamicValue::Int(read_unaligned::<i16>(bytes)? as i64),
+        "i32" => DynamicValue::Int(read_unaligned::<i32>(bytes)? as i64),
+        "i64" => DynamicValue::Int(read_unaligned::<i64>(bytes)?),
+        "u8" => DynamicValue::UInt(read_unaligned::<u8>(bytes)? as u64),
+        "u16" => DynamicValue::UInt(read_unaligned::<u16>(bytes)? as u64),
+        "u32" => DynamicValue::UInt(read_unaligned::<u32>(bytes)? as u64),
+        "u64" => DynamicValue::UInt(read_unaligned::<u64>(bytes)?),
+        "f32" => DynamicValue::Float(read_unaligned::<f32>(bytes)? as f64),
+        "f64" => DynamicValue::Float(read_unaligned::<f64>(bytes)?),
+        _ => return None,
+    })
+}
+
+impl ReaderContext {
+    /// Reads the object at `address` as a `class_name` using the SDK layout,
+    /// following object pointers up to `depth` levels.
+    ///
+    /// ## Example:
+    /// ```
+    /// let ship = ctx.read_object_dynamic(actor.base_address, "Ship", 1)?;
+    /// println!("{}", serde_json::to_string_pretty(&ship).unwrap());
+    /// ```
+    pub fn read_object_dynamic(
+        &self,
+        address: RemotePtr<UObject>,
+        class_name: &str,
+        depth: u32,
+    ) -> Result<DynamicValue, MemoryReaderError> {
+        let sdk = self.sdk().get();
+        if sdk.get_flattened_attributes(class_name).is_none() {
+            return Err(MemoryReaderError::UnknownType(format!(
+                "Class or Struct \"{}\" does not exist",
+                class_name
+            )));
+        }
+        if !address.is_valid() {
+            return Err(MemoryReaderError::MemoryReadingError(format!(
+                "Invalid pointer {:?}",
+                address
+            )));
+        }
+
+        let size = sdk.get_full_size(class_name) as usize;
+        let bytes = read_bytes(self, address.address(), size)?;
+        Ok(self.decode_struct(&sdk, &bytes, address.address(), class_name, depth))
+    }
+
+    /// Name of the runtime class of an object.
+    pub fn read_class_name(&self, object: RemotePtr<UObject>) -> Result<String, MemoryReaderError> {
+        let u_class = object.read(self)?.u_class;
+        let name = u_class.read(self)?.name;
+        self.read_gname(name.index)
+    }
+
+    fn decode_struct(
+        &self,
+        sdk: &SdkService,
+        bytes: &[u8],
+        address: usize,
+        name: &str,
+        depth: u32,
+    ) -> DynamicValue {
+        let mut attributes = match sdk.get_flattened_attributes(name) {
+            Some(v) => v,
+            None => {
+                return DynamicValue::Unknown {
+                    type_: name.to_string(),
+                    bytes: bytes.to_vec(),
+                }
+            }
+        };
+        attributes.sort_by_key(|(_, v)| v.Offset);
+
+        let mut members = vec![];
+        for (_, attribute) in attributes {
+            let start = attribute.Offset as usize;
+            let end = start + attribute.Size as usize;
+            let value = match bytes.get(start..end) {
+                Some([byte]) if attribute.BitMask.is_some() => {
+                    DynamicValue::Bool(attribute.bool_from_byte(*byte))
+                }
+                Some(v) => self.decode_value(sdk, v, address + start, &attribute.Type, depth),
+                None => DynamicValue::Error(format!(
+                    "0x{:X} (size 0x{:X}) is outside of {} (size 0x{:X})",
+                    attribute.Offset,
+                    attribute.Size,
+                    name,
+                    bytes.len()
+                )),
+            };
+            members.push((attribute.Name.clone(), value));
+        }
+        DynamicValue::Struct(members)
+    }
+
+    fn decode_value(
+        &self,
+        sdk: &SdkService,
+        bytes: &[u8],
+        address: usize,
+        type_name: &str,
+        depth: u32,
+    ) -> DynamicValue {
+        let type_name = type_name.trim();
+        let unknown = || DynamicValue::Unknown {
+            type_: type_name.to_string(),
+            bytes: bytes.to_vec(),
+        };
+
+        if type_name == "bool" {
+            return match bytes.first() {
+                Some(v) => DynamicValue::Bool(*v != 0),
+                None => unknown(),
+            };
+        }
+        if let Some((rust_type, size)) = primitive_type(type_name) {
+            if size as usize == bytes.len() {
+                return decode_primitive(rust_type, bytes).unwrap_or_else(unknown);
+            }
+            return unknown();
+        }
+
+        let result = match type_name {
+            "FName" => match read_unaligned::<u32>(bytes) {
+                Some(index) => self.read_gname(index),
+                None => return unknown(),
+            },
+            "FString" => read_fstring(self, address),
+            "FText" => read_ftext(self, address),
+            _ => {
+                if type_name.ends_with('*') {
+                    return match read_unaligned::<u64>(bytes) {
+                        Some(v) => self.decode_pointer(sdk, v as usize, type_name, depth),
+                        None => unknown(),
+                    };
+                }
+                if let Some(inner) = generic_argument(type_name, "TArray") {
+                    return self.decode_array(sdk, address, inner, depth);
+                }
+                if generic_argument(type_name, "TEnumAsByte").is_some() {
+                    return decode_primitive("u8", bytes).unwrap_or_else(unknown);
+                }
+                return match sdk.resolve_type_name(type_name) {
+                    Some(name) => self.decode_struct(sdk, bytes, address, name, depth),
+                    None => unknown(),
+                };
+            }
+        };
+        match result {
+            Ok(v) => DynamicValue::String(v),
+            Err(v) => DynamicValue::Error(format!("{:?}", v)),
+        }
+    }
+
+    /// Pointers to classes are followed using the runtime class of the object,
+    /// or the declared one when the runtime class is not in the SDK.
+    fn decode_pointer(
+        &self,
+        sdk: &SdkService,
+        address: usize,
+        type_name: &str,
+        depth: u32,
+    ) -> DynamicValue {
+        let address = RemotePtr::<UObject>::new(address);
+        if address.is_null() {
+            return DynamicValue::Null;
+        }
+        let declared_class = sdk
+            .resolve_type_name(type_name)
+            .filter(|v| sdk.classes.contains_key(*v));
+        if declared_class.is_none() {
+            return DynamicValue::Pointer {
+                address,
+                class: None,
+                object: None,
+            };
+        }
+
+        let class = self.read_class_name(address).ok();
+        let object = if depth == 0 {
+            None
+        } else {
+            let class_name = match &class {
+                Some(v) if sdk.classes.contains_key(v) => v.as_str(),
+                _ => declared_class.unwrap(),
+            };
+            let object = match self.read_object_dynamic(address, class_name, depth - 1) {
+                Ok(v) => v,
+                Err(v) => DynamicValue::Error(format!("{:?}", v)),
+            };
+            Some(Box::new(object))
+        };
+        DynamicValue::Pointer {
+            address,
+            class,
+            object,
+        }
+    }
+
+    fn decode_array(
+        &self,
+        sdk: &SdkService,
+        address: usize,
+        item_type: &str,
+        depth: u32,
+    ) -> DynamicValue {
+        let item_size = match self.type_size(sdk, item_type) {
+            Some(v) => v,
+            None => return DynamicValue::Error(format!("Unknown size of TArray<{}>", item_type)),
+        };
+        let header = match read_array_header(self, address) {
+            Ok(v) => v,
+            Err(v) => return DynamicValue::Error(format!("{:?}", v)),
+        };
+        if header.count == 0 {
+            return DynamicValue::Array(vec![]);
+        }
+
+        let data = header.data as usize;
+        let bytes = match read_bytes(self, data, item_size * header.count as usize) {
+            Ok(v) => v,
+            Err(v) => return DynamicValue::Error(format!("{:?}", v)),
+        };
+        DynamicValue::Array(
+            bytes
+                .chunks_exact(item_size)
+                .enumerate()
+                .map(|(index, chunk)| {
+                    self.decode_value(sdk, chunk, data + index * item_size, item_type, depth)
+                })
+                .collect(),
+        )
+    }
+
+    fn type_size(&self, sdk: &SdkService, type_name: &str) -> Option<usize> {
+        let type_name = type_name.trim();
+        if let Some((_, size)) = primitive_type(type_name) {
+            return Some(size as usize);
+        }
+        if type_name.ends_with('*') {
+            return Some(8);
+        }
+        match type_name {
+            "FName" => Some(8),
+            "FString" => Some(0x10),
+            "FText" => Some(0x18),
+            _ if generic_argument(type_name, "TArray").is_some() => Some(0x10),
+            _ if generic_argument(type_name, "TEnumAsByte").is_some() => Some(1),
+            _ => sdk
+                .resolve_type_name(type_name)
+                .map(|v| sdk.get_full_size(v) as usize),
+        }
+    }
+}
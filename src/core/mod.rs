@@ -1 +1,3 @@
+pub mod context;
+pub mod dynamic;
 pub mod reader;
@@ -1,97 +1,353 @@
 use std::collections::HashMap;
 use std::ffi::c_void;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 use std::mem::size_of;
-use std::str::from_utf8;
-use std::sync::OnceLock;
 
 use toy_arms::external::error::TAExternalError;
+use toy_arms::external::module::Module;
 use toy_arms::external::process::Process;
 use toy_arms::external::read;
 
 use sysinfo::{PidExt, ProcessExt, System, SystemExt};
 
-use crate::structs::tarray::{TArray, TArrayStruct};
+use crate::core::context::ReaderContext;
+use crate::services::sdk::{SdkResolved, SdkService};
+use crate::structs::pod::{read_unaligned, Pod};
+use crate::structs::remote_ptr::RemotePtr;
+use crate::structs::tarray::{TArray, TArrayHeader, TArrayStruct};
+use crate::structs::tset::{
+    AllocationFlags, TMap, TMapStruct, TSet, TSetStruct, TSparseArrayHeader,
+};
+use crate::structs::unreal::UObject;
 
 const UWORLDPATTERN: &'static str = "48 8B 05 ? ? ? ? 48 8B 88 ? ? ? ? 48 85 C9 74 06 48 8B 49 70";
 const GOBJECTPATTERN: &'static str = "89 0D ? ? ? ? 48 8B DF 48 89 5C 24";
 const GNAMEPATTERN: &'static str = "48 8B 1D ? ? ? ? 48 85 DB 75 ? B9 08 04 00 00";
 
-struct MyProcess {
-    pub name: &'static str,
-    pub id: u32,
-    pub handle: usize,
+/// Which process to read, the game by default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessTarget {
+    Name(String),
+    Pid(u32),
 }
 
-fn process() -> &'static MyProcess {
-    static PROCESS: OnceLock<MyProcess> = OnceLock::new();
-    PROCESS.get_or_init(|| {
-        let process = Process::from_process_name("SoTGame.exe").unwrap();
-        MyProcess {
-            name: process.name,
-            id: process.id,
-            handle: process.handle as usize,
+impl Default for ProcessTarget {
+    fn default() -> Self {
+        ProcessTarget::Name("SoTGame.exe".to_string())
+    }
+}
+
+impl fmt::Display for ProcessTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcessTarget::Name(v) => write!(f, "{}", v),
+            ProcessTarget::Pid(v) => write!(f, "pid {}", v),
         }
-    })
+    }
 }
 
-pub fn read_pointer<T>(address: *mut T) -> Result<T, TAExternalError> {
-    let mut target_buffer: T = unsafe { std::mem::zeroed() };
-    let _bytes_read = 0;
-    read::<T>(
-        &(process().handle as *mut c_void),
-        address as usize,
-        size_of::<T>(),
-        &mut target_buffer as *mut T,
-    )?;
-    Ok(target_buffer)
+/// Running processes matching `target` as `(pid, name)`, sorted by pid.
+pub fn find_processes(target: &ProcessTarget) -> Vec<(u32, String)> {
+    let mut system = System::new();
+    system.refresh_processes();
+    let mut processes = system
+        .processes()
+        .values()
+        .filter(|v| match target {
+            ProcessTarget::Name(name) => v.name() == name,
+            ProcessTarget::Pid(pid) => v.pid().as_u32() == *pid,
+        })
+        .map(|v| (v.pid().as_u32(), v.name().to_string()))
+        .collect::<Vec<(u32, String)>>();
+    processes.sort();
+    processes
+}
+
+/// Something remote structs can be read from.
+pub trait MemorySource {
+    fn read_into(&self, address: usize, buffer: &mut [u8]) -> Result<(), MemoryReaderError>;
+
+    fn read<T: Pod>(&self, address: usize) -> Result<T, MemoryReaderError>
+    where
+        Self: Sized,
+    {
+        let mut buffer = vec![0; size_of::<T>()];
+        self.read_into(address, &mut buffer)?;
+        Ok(read_unaligned(&buffer))
+    }
+
+    /// Upper bound on the number of elements read from a remote TArray, so
+    /// that a garbage header cannot make us allocate gigabytes.
+    fn max_array_count(&self) -> u32 {
+        DEFAULT_MAX_ARRAY_COUNT
+    }
+}
+
+/// Memory of a running process.
+pub struct ProcessMemory {
+    pid: u32,
+    handle: usize,
+}
+
+impl ProcessMemory {
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+}
+
+impl MemorySource for ProcessMemory {
+    fn read_into(&self, address: usize, buffer: &mut [u8]) -> Result<(), MemoryReaderError> {
+        read::<u8>(
+            &(self.handle as *mut c_void),
+            address,
+            buffer.len(),
+            buffer.as_mut_ptr(),
+        )?;
+        Ok(())
+    }
 }
 
-pub fn read_bytes(address: usize, size: usize) -> Vec<u8> {
+pub fn read_bytes<S: MemorySource>(
+    source: &S,
+    address: usize,
+    size: usize,
+) -> Result<Vec<u8>, MemoryReaderError> {
     let mut target_buffer: Vec<u8> = vec![0; size];
-    read::<u8>(
-        &(process().handle as *mut c_void),
-        address,
-        size,
-        target_buffer.as_mut_ptr(),
-    )
-    .unwrap();
-    target_buffer
+    source.read_into(address, &mut target_buffer)?;
+    Ok(target_buffer)
 }
 
-pub fn read_array<T>(address: usize) -> TArray<T> {
-    let buffer = read_bytes(address, 12);
+/// `MemorySource::max_array_count` unless `--max-array-count` is given.
+pub const DEFAULT_MAX_ARRAY_COUNT: u32 = 0x10000;
+
+/// Highest user-mode address on x64 Windows, anything above is not canonical.
+pub const MAX_USER_ADDRESS: u64 = 0x0000_7FFF_FFFF_FFFF;
 
-    let mut base_address_bytes = [0; 8];
-    base_address_bytes.copy_from_slice(&buffer[0..8]);
-    let base_address: u64 = u64::from_le_bytes(base_address_bytes);
+/// Reads and validates the `{ Data, Num, Max }` header of a TArray.
+pub fn read_array_header<S: MemorySource>(
+    source: &S,
+    address: usize,
+) -> Result<TArrayHeader, MemoryReaderError> {
+    let header = source.read::<TArrayHeader>(address)?;
+    validate_array_header(source, address, header)
+}
+
+/// Checks a TArray header that was read at `address` as part of a larger struct.
+pub fn validate_array_header<S: MemorySource>(
+    source: &S,
+    address: usize,
+    header: TArrayHeader,
+) -> Result<TArrayHeader, MemoryReaderError> {
+    let (data, count, max) = (header.data, header.count, header.max);
+
+    if count > max {
+        return Err(MemoryReaderError::InvalidArray(format!(
+            "0x{:X}: Num {} is greater than Max {} (data 0x{:X})",
+            address, count, max, data
+        )));
+    }
+    if count > source.max_array_count() {
+        return Err(MemoryReaderError::InvalidArray(format!(
+            "0x{:X}: Num {} is above the limit (data 0x{:X})",
+            address, count, data
+        )));
+    }
+    if count > 0 && (data == 0 || data > MAX_USER_ADDRESS) {
+        return Err(MemoryReaderError::InvalidArray(format!(
+            "0x{:X}: invalid data pointer 0x{:X}",
+            address, data
+        )));
+    }
+    Ok(header)
+}
 
-    let mut count_bytes = [0; 4];
-    count_bytes.copy_from_slice(&buffer[8..12]);
-    let count: u32 = u32::from_le_bytes(count_bytes);
+pub fn read_array<T: Pod, S: MemorySource>(
+    source: &S,
+    address: usize,
+) -> Result<TArray<T>, MemoryReaderError> {
+    let header = read_array_header(source, address)?;
+    if header.count == 0 {
+        return Ok(TArray::new(vec![], 0));
+    }
 
     let item_size = size_of::<T>();
-    let raw_bytes = read_bytes(base_address as usize, item_size * count as usize);
+    let raw_bytes = read_bytes(
+        source,
+        header.data as usize,
+        item_size * header.count as usize,
+    )?;
+
+    Ok(TArray::new(raw_bytes, header.count))
+}
+
+pub fn read_array_sized<S: MemorySource>(
+    source: &S,
+    address: usize,
+    item_size: usize,
+) -> Result<TArrayStruct, MemoryReaderError> {
+    let header = read_array_header(source, address)?;
+
+    let array = TArrayStruct::new(header.data as usize, item_size, header.count);
+    Ok(array)
+}
 
-    TArray::new(raw_bytes, count)
+/// Offset of the string inside the text data an `FText` points to.
+const FTEXT_DATA_STRING_OFFSET: usize = 0x28;
+
+/// Reads an `FString`: a TArray of UTF-16 characters including the terminator.
+pub fn read_fstring<S: MemorySource>(
+    source: &S,
+    address: usize,
+) -> Result<String, MemoryReaderError> {
+    let header = read_array_header(source, address)?;
+    if header.count == 0 {
+        return Ok(String::new());
+    }
+
+    let raw_bytes = read_bytes(source, header.data as usize, header.count as usize * 2)?;
+    let mut u16_buffer: Vec<u16> = raw_bytes
+        .chunks_exact(2)
+        .map(|a| u16::from_le_bytes([a[0], a[1]]))
+        .collect();
+    if let Some(i) = u16_buffer.iter().position(|c| *c == 0) {
+        u16_buffer.truncate(i);
+    }
+
+    String::from_utf16(&u16_buffer).map_err(|_| MemoryReaderError::ByteToStringConversion)
+}
+
+/// Best effort `FText` reading: follows the text data pointer and reads the
+/// display string stored in it. Empty texts have no text data.
+pub fn read_ftext<S: MemorySource>(
+    source: &S,
+    address: usize,
+) -> Result<String, MemoryReaderError> {
+    let text_data = source.read::<u64>(address)?;
+    if text_data == 0 {
+        return Ok(String::new());
+    }
+    if text_data > MAX_USER_ADDRESS {
+        return Err(MemoryReaderError::MemoryReadingError(format!(
+            "0x{:X}: invalid text data pointer 0x{:X}",
+            address, text_data
+        )));
+    }
+    read_fstring(source, text_data as usize + FTEXT_DATA_STRING_OFFSET)
+}
+
+/// Reads the header and allocation flags of the sparse array backing a TSet or
+/// a TMap, validating its element array like `read_array_header` does.
+pub fn read_sparse_array<S: MemorySource>(
+    source: &S,
+    address: usize,
+) -> Result<(TSparseArrayHeader, AllocationFlags), MemoryReaderError> {
+    let header = source.read::<TSparseArrayHeader>(address)?;
+    validate_array_header(source, address, header.data)?;
+    let flags = header.allocation_flags;
+    let (count, num_bits, secondary_data) =
+        (header.data.count, flags.num_bits, flags.secondary_data);
+
+    if num_bits < 0 || (num_bits as u32) < count {
+        return Err(MemoryReaderError::InvalidArray(format!(
+            "0x{:X}: {} allocation bits for {} elements",
+            address, num_bits, count
+        )));
+    }
+
+    let word_count = (count as usize).div_ceil(32);
+    let inline_data = flags.inline_data;
+    let inline_bits = inline_data.len() * 32;
+    if secondary_data == 0 && count as usize > inline_bits {
+        return Err(MemoryReaderError::InvalidArray(format!(
+            "0x{:X}: {} elements but only {} inline allocation bits",
+            address, count, inline_bits
+        )));
+    }
+    let words = if secondary_data != 0 {
+        if secondary_data > MAX_USER_ADDRESS {
+            return Err(MemoryReaderError::InvalidArray(format!(
+                "0x{:X}: invalid allocation flags pointer 0x{:X}",
+                address, secondary_data
+            )));
+        }
+        read_bytes(source, secondary_data as usize, word_count * 4)?
+            .chunks_exact(4)
+            .map(|v| u32::from_le_bytes([v[0], v[1], v[2], v[3]]))
+            .collect()
+    } else {
+        inline_data.iter().take(word_count).copied().collect()
+    };
+
+    Ok((header, AllocationFlags::new(words)))
+}
+
+pub fn read_set<T: Pod, S: MemorySource>(
+    source: &S,
+    address: usize,
+) -> Result<TSet<T>, MemoryReaderError> {
+    let (header, flags) = read_sparse_array(source, address)?;
+    let (data, count) = (header.data.data, header.data.count);
+    if count == 0 {
+        return Ok(TSet::new(vec![], &flags));
+    }
+
+    let raw_bytes = read_bytes(
+        source,
+        data as usize,
+        TSet::<T>::element_size() * count as usize,
+    )?;
+    Ok(TSet::new(raw_bytes, &flags))
 }
 
-pub fn read_array_sized(address: usize, item_size: usize) -> TArrayStruct {
-    let buffer = read_bytes(address, 12);
+pub fn read_map<K: Pod, V: Pod, S: MemorySource>(
+    source: &S,
+    address: usize,
+) -> Result<TMap<K, V>, MemoryReaderError> {
+    let (header, flags) = read_sparse_array(source, address)?;
+    let (data, count) = (header.data.data, header.data.count);
+    if count == 0 {
+        return Ok(TMap::new(vec![], &flags));
+    }
 
-    let mut base_address_bytes = [0; 8];
-    base_address_bytes.copy_from_slice(&buffer[0..8]);
-    let base_address: u64 = u64::from_le_bytes(base_address_bytes);
+    let raw_bytes = read_bytes(
+        source,
+        data as usize,
+        TMap::<K, V>::element_size() * count as usize,
+    )?;
+    Ok(TMap::new(raw_bytes, &flags))
+}
 
-    let mut count_bytes = [0; 4];
-    count_bytes.copy_from_slice(&buffer[8..12]);
-    let count: u32 = u32::from_le_bytes(count_bytes);
+/// `element_size` is the size of a whole set element, see `set_element_size`.
+pub fn read_set_sized<S: MemorySource>(
+    source: &S,
+    address: usize,
+    element_size: usize,
+) -> Result<TSetStruct, MemoryReaderError> {
+    let (header, flags) = read_sparse_array(source, address)?;
+
+    Ok(TSetStruct::new(
+        header.data.data as usize,
+        element_size,
+        header.data.count,
+        flags,
+    ))
+}
 
-    let array = TArrayStruct::new(base_address as usize, item_size, count);
-    array
+pub fn read_map_sized<S: MemorySource>(
+    source: &S,
+    address: usize,
+    element_size: usize,
+    value_offset: usize,
+) -> Result<TMapStruct, MemoryReaderError> {
+    let set = read_set_sized(source, address, element_size)?;
+    Ok(TMapStruct::new(set, value_offset))
 }
 
-pub fn find_dma_addy<T>(address: usize, mut offsets: Vec<u32>) -> Result<T, TAExternalError> {
+pub fn find_dma_addy<T: Pod, S: MemorySource>(
+    source: &S,
+    address: usize,
+    mut offsets: Vec<u32>,
+) -> Result<T, MemoryReaderError> {
     if offsets.is_empty() {
         panic!("Offsets vector is empty. Expected at least one element.");
     }
@@ -100,187 +356,426 @@ pub fn find_dma_addy<T>(address: usize, mut offsets: Vec<u32>) -> Result<T, TAEx
 
     for offset in offsets {
         current_address = current_address + offset as usize;
-        current_address = read_pointer(current_address as *mut _)?;
+        current_address = source.read::<usize>(current_address)?;
     }
-    read_pointer::<T>((current_address + last_offset as usize) as *mut T)
+    source.read::<T>(current_address + last_offset as usize)
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum MemoryReaderError {
     InitializationError(String),
     MemoryReadingError(String),
+    InvalidArray(String),
     ByteToStringConversion,
+    UnknownType(String),
+}
+
+impl From<TAExternalError> for MemoryReaderError {
+    fn from(error: TAExternalError) -> Self {
+        MemoryReaderError::MemoryReadingError(format!("{:?}", error))
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct ActorInfo {
     pub id: u32,
     pub raw_name: String,
-    pub base_address: usize,
+    pub base_address: RemotePtr<UObject>,
 }
 
-pub struct MemoryReader {
-    u_world_base: usize,
-    g_object_base: usize,
-    g_name_base: usize,
-    g_name_start_address: u64,
+/// Identifies an actor across ticks. The FName index alone is shared by every
+/// actor of a class, and addresses get reused by actors of other classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ActorKey {
+    pub base_address: RemotePtr<UObject>,
+    pub name_id: u32,
 }
 
-impl MemoryReader {
-    pub fn new(process_name: &'static str) -> Self {
-        let process_ = Process::from_process_name("SoTGame.exe").unwrap();
-        let mut module = process_.get_module_info("SoTGame.exe").unwrap();
-
-        let u_world_base = module
-            .find_pattern(UWORLDPATTERN)
-            .expect("Could not find u_world_base offsets");
-        let g_object_base = module
-            .find_pattern(GOBJECTPATTERN)
-            .expect("Could not find g_object_base offsets");
-        let g_name_base = module
-            .find_pattern(GNAMEPATTERN)
-            .expect("Could not find g_name_base offsets");
-
-        let g_name_offset =
-            read_pointer((module.base_address + g_name_base + 3) as *mut u32).unwrap();
-        let g_name_ptr = module.base_address + g_name_base + (g_name_offset as usize + 7);
-
-        let g_name_start_address = read_pointer(g_name_ptr as *mut u64).unwrap();
-
-        Self {
-            u_world_base,
-            g_object_base,
-            g_name_base,
-            g_name_start_address,
+impl ActorInfo {
+    pub fn key(&self) -> ActorKey {
+        ActorKey {
+            base_address: self.base_address,
+            name_id: self.id,
         }
     }
+}
 
-    pub fn check_process_is_active(&self) -> bool {
-        let s = System::new_all();
-        for process_ in s.processes_by_exact_name(process().name) {
-            if process_.pid().as_u32() == process().id {
-                return true;
-            }
-        }
-        false
-    }
+/// Engine globals of the game, found by signature scanning.
+#[derive(Debug, Clone, Copy)]
+pub struct GameGlobals {
+    /// Address of the `GWorld` global, which changes on map transitions.
+    pub u_world: RemotePtr<RemotePtr<UObject>>,
+    pub g_objects: usize,
+    /// First chunk of the name table.
+    pub g_names: u64,
+}
 
-    pub fn read_string_default_size(&self, address: usize) -> Result<String, MemoryReaderError> {
-        self.read_string(address, 124)
-    }
+impl GameGlobals {
+    /// Fails while the game is still starting and its module is not fully
+    /// mapped yet.
+    pub fn find<S: MemorySource>(
+        source: &S,
+        module: &mut Module,
+    ) -> Result<Self, MemoryReaderError> {
+        let find_pattern = |module: &mut Module, pattern: &str, name: &str| {
+            module.find_pattern(pattern).ok_or_else(|| {
+                MemoryReaderError::InitializationError(format!("Could not find {} offsets", name))
+            })
+        };
+        let base_address = module.base_address;
+        let u_world_base = find_pattern(module, UWORLDPATTERN, "u_world_base")?;
+        let g_object_base = find_pattern(module, GOBJECTPATTERN, "g_object_base")?;
+        let g_name_base = find_pattern(module, GNAMEPATTERN, "g_name_base")?;
 
-    pub fn read_string(&self, address: usize, size: usize) -> Result<String, MemoryReaderError> {
-        let buffer = read_bytes(address, size);
+        let u_world_offset = source.read::<u32>(base_address + u_world_base + 3)? as usize;
+        let u_world = RemotePtr::new(base_address + u_world_base + u_world_offset + 7);
 
-        let i = match buffer.iter().position(|r| r == &b'\x00') {
-            Some(v) => v,
-            None => buffer.len(),
-        };
+        let g_objects_offset = source.read::<u64>(base_address + g_object_base + 2)? as usize;
+        let g_objects = base_address + g_object_base + g_objects_offset + 22;
 
-        let result = from_utf8(&buffer[0..i]);
+        let g_name_offset = source.read::<u32>(base_address + g_name_base + 3)?;
+        let g_name_ptr = base_address + g_name_base + (g_name_offset as usize + 7);
+        let g_names = source.read::<u64>(g_name_ptr)?;
 
-        match result {
-            Ok(v) => Ok(String::from(v)),
-            Err(_) => Ok(self.read_name_string(address, size)?),
-        }
+        Ok(Self {
+            u_world,
+            g_objects,
+            g_names,
+        })
     }
+}
 
-    pub fn read_name_string(
-        &self,
-        address: usize,
-        size: usize,
-    ) -> Result<String, MemoryReaderError> {
-        let target_buffer = read_bytes(address, size);
-
-        let i = target_buffer
-            .windows(3)
-            .position(|window| window == b"\x00\x00\x00")
-            .ok_or(MemoryReaderError::ByteToStringConversion)?;
-
-        let u16_buffer: Vec<u16> = target_buffer[0..i]
-            .chunks_exact(2)
-            .into_iter()
-            .map(|a| u16::from_ne_bytes([a[0], a[1]]))
-            .collect();
+/// Opens the single process matching `target` and finds its globals.
+pub fn open_process(
+    target: &ProcessTarget,
+) -> Result<(ProcessMemory, GameGlobals), MemoryReaderError> {
+    let (pid, process_name) = match find_processes(target).as_slice() {
+        [v] => v.clone(),
+        [] => {
+            return Err(MemoryReaderError::InitializationError(format!(
+                "{} is not running",
+                target
+            )))
+        }
+        _ => {
+            return Err(MemoryReaderError::InitializationError(format!(
+                "Several {} processes are running, select one with --pid",
+                target
+            )))
+        }
+    };
+    let process_ = Process::from_pid(pid).ok_or_else(|| {
+        MemoryReaderError::InitializationError(format!("Could not open pid {}", pid))
+    })?;
+    let memory = ProcessMemory {
+        pid,
+        handle: process_.handle as usize,
+    };
+    let mut module = process_
+        .get_module_info(&process_name)
+        .map_err(|v| MemoryReaderError::InitializationError(format!("{:?}", v)))?;
+
+    let globals = GameGlobals::find(&memory, &mut module)?;
+    Ok((memory, globals))
+}
 
-        match String::from_utf16(&u16_buffer) {
-            Ok(v) => Ok(v),
-            Err(_) => Err(MemoryReaderError::ByteToStringConversion),
+/// Offsets of the level hierarchy. `ULevel::Actors` is not a UPROPERTY and is
+/// missing from most dumps, hence the fallbacks for the current game build.
+#[derive(Clone, Copy)]
+struct WorldOffsets {
+    levels: u32,
+    persistent_level: Option<u32>,
+    actors: u32,
+}
+
+impl WorldOffsets {
+    /// Resolved once per SDK generation, so the fallback notices are only
+    /// printed when an SDK is loaded.
+    fn resolve(sdk: &SdkService) -> Self {
+        let fallback = |path: &str, offset: u32| {
+            eprintln!(
+                "{} is missing from the SDK, using the fallback offset 0x{:X}",
+                path, offset
+            );
+            offset
+        };
+        Self {
+            levels: sdk
+                .try_get_offset("World.Levels")
+                .unwrap_or_else(|| fallback("World.Levels", 0x150)),
+            persistent_level: sdk.try_get_offset("World.PersistentLevel"),
+            actors: sdk
+                .try_get_offset("Level.Actors")
+                .or_else(|| sdk.try_get_offset("Level.AActors"))
+                .unwrap_or_else(|| fallback("Level.Actors", 0xa0)),
         }
     }
+}
 
-    pub fn read_gname(&self, actor_id: u32) -> Result<String, MemoryReaderError> {
-        let actor_id = u64::from(actor_id);
-        let name_ptr =
-            read_pointer((self.g_name_start_address + actor_id / 0x4000 * 0x8) as *mut u64)
-                .unwrap();
-        let name = read_pointer((name_ptr + 0x8 * (actor_id % 0x4000)) as *mut u64).unwrap();
-        Ok(self.read_string((name + 0x10) as usize, 64))?
-    }
+#[derive(Debug, Clone)]
+pub struct LevelInfo {
+    /// Name of the world package the level belongs to.
+    pub name: String,
+    pub base_address: RemotePtr<UObject>,
+    pub is_persistent: bool,
 }
 
 pub struct SoTMemoryReader {
-    pub rm: MemoryReader,
-    world_address: usize,
+    ctx: ReaderContext,
+    offsets: SdkResolved<WorldOffsets>,
+    levels: Vec<LevelInfo>,
 }
 
 impl SoTMemoryReader {
-    pub fn new(process_name: &'static str) -> Result<Self, MemoryReaderError> {
-        let rm = MemoryReader::new(process_name);
-        let process_ = Process::from_process_name("SoTGame.exe").unwrap();
-        let module = process_.get_module_info("SoTGame.exe").unwrap();
-        let base_address = module.base_address;
+    pub fn new(ctx: ReaderContext) -> Self {
+        Self {
+            offsets: SdkResolved::new(ctx.sdk(), WorldOffsets::resolve),
+            ctx,
+            levels: vec![],
+        }
+    }
 
-        let u_world_offset =
-            read_pointer::<u32>((base_address + rm.u_world_base + 3) as *mut u32).unwrap() as usize;
-        let u_world_ptr = (base_address + rm.u_world_base + u_world_offset + 7) as *mut u64;
-        let world_address = read_pointer::<u64>(u_world_ptr).unwrap() as usize;
-        let _g_objects_offset =
-            read_pointer::<u64>((base_address + rm.g_object_base + 2) as *mut u64).unwrap()
-                as usize;
-        let _g_objects_address = base_address + rm.g_object_base + _g_objects_offset + 22;
+    pub fn ctx(&self) -> &ReaderContext {
+        &self.ctx
+    }
 
-        Ok(Self { rm, world_address })
+    /// Current UWorld, read again on every call. `None` while no world is
+    /// loaded, e.g. when travelling between servers.
+    pub fn read_world(&self) -> Result<Option<RemotePtr<UObject>>, MemoryReaderError> {
+        let world = self.ctx.globals().u_world.read(&self.ctx)?;
+        Ok(Some(world).filter(|v| v.is_valid()))
     }
 
-    pub fn read_actors(
-        &mut self,
-        actor_name_map: &mut HashMap<u32, ActorInfo>,
-    ) -> Result<(), MemoryReaderError> {
-        let levels_pointer_table = read_array::<*mut c_void>((self.world_address + 0x150) as usize);
+    /// Levels of the current world as of the last `read_actors`.
+    pub fn levels(&self) -> &[LevelInfo] {
+        &self.levels
+    }
 
-        for level_base_address in levels_pointer_table.iter() {
-            let actors_pointer_table =
-                read_array::<*mut c_void>(level_base_address as usize + 0xa0);
+    fn read_levels(
+        &self,
+        world: RemotePtr<UObject>,
+        offsets: &WorldOffsets,
+    ) -> Result<Vec<LevelInfo>, MemoryReaderError> {
+        let persistent_level = match offsets.persistent_level {
+            Some(offset) => world.field::<RemotePtr<UObject>>(offset).read(&self.ctx)?,
+            None => RemotePtr::null(),
+        };
+        let mut level_pointers = read_array::<RemotePtr<UObject>, _>(
+            &self.ctx,
+            world.field::<()>(offsets.levels).address(),
+        )?
+        .into_vec();
+        if persistent_level.is_valid() && !level_pointers.contains(&persistent_level) {
+            level_pointers.insert(0, persistent_level);
+        }
 
-            if actors_pointer_table.count == 0 {
-                println!("This level has no actors");
+        let mut levels = vec![];
+        for level in level_pointers {
+            if !level.is_valid() {
                 continue;
             }
+            let object = match level.read(&self.ctx) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            let name_id = match object.outer.read(&self.ctx) {
+                Ok(outer) => outer.name.index,
+                Err(_) => object.name.index,
+            };
+            levels.push(LevelInfo {
+                name: self.ctx.read_gname(name_id).unwrap_or_default(),
+                base_address: level,
+                is_persistent: level == persistent_level,
+            });
+        }
+        Ok(levels)
+    }
+
+    /// Every actor of every level of the current world, read from scratch on
+    /// each call. Empty when no world is loaded, an error only when the world
+    /// could not be read.
+    pub fn read_actors(&mut self) -> Result<HashMap<ActorKey, ActorInfo>, MemoryReaderError> {
+        let offsets = *self.offsets.get(self.ctx.sdk());
+        let world = match self.read_world()? {
+            Some(v) => v,
+            None => {
+                self.levels.clear();
+                return Ok(HashMap::new());
+            }
+        };
+        self.levels = self.read_levels(world, &offsets)?;
+
+        let mut actors = HashMap::new();
+        for level_index in 0..self.levels.len() {
+            let level = self.levels[level_index].base_address;
+            let actors_pointer_table = match read_array::<RemotePtr<UObject>, _>(
+                &self.ctx,
+                level.field::<()>(offsets.actors).address(),
+            ) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
 
             for actor_base_address in actors_pointer_table.iter() {
-                let actor_id = read_pointer((actor_base_address as usize + 0x18) as *mut u32);
-                if actor_id.is_err() {
+                if !actor_base_address.is_valid() {
                     continue;
                 }
-                let actor_id = actor_id.unwrap();
-                let _ = if let Some(_) = actor_name_map.get(&actor_id) {
-                    continue;
-                } else {
-                    let name = match self.rm.read_gname(actor_id) {
-                        Ok(v) => v,
-                        Err(_) => continue,
-                    };
-                    let new_actor_info = ActorInfo {
-                        id: actor_id,
-                        raw_name: name,
-                        base_address: actor_base_address as usize,
-                    };
-                    actor_name_map.insert(actor_id, new_actor_info);
-                    actor_name_map.get(&actor_id).unwrap()
+                let actor_id = match actor_base_address.field::<u32>(0x18).read(&self.ctx) {
+                    Ok(v) => v,
+                    Err(_) => continue,
                 };
+                let name = match self.ctx.read_gname(actor_id) {
+                    Ok(v) => v,
+                    Err(_) => continue,
+                };
+                let actor_info = ActorInfo {
+                    id: actor_id,
+                    raw_name: name,
+                    base_address: actor_base_address,
+                };
+                actors.insert(actor_info.key(), actor_info);
             }
         }
-        Ok(())
+        Ok(actors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: usize = 0x1000;
+    const DATA: usize = 0x2000;
+    const FLAGS: usize = 0x3000;
+
+    /// Memory made of separate regions, reading anything else fails.
+    #[derive(Default)]
+    struct FakeMemory {
+        regions: Vec<(usize, Vec<u8>)>,
+    }
+
+    impl FakeMemory {
+        fn with(mut self, address: usize, bytes: Vec<u8>) -> Self {
+            self.regions.push((address, bytes));
+            self
+        }
+    }
+
+    impl MemorySource for FakeMemory {
+        fn read_into(&self, address: usize, buffer: &mut [u8]) -> Result<(), MemoryReaderError> {
+            for (start, bytes) in &self.regions {
+                if address >= *start && address + buffer.len() <= start + bytes.len() {
+                    let offset = address - start;
+                    buffer.copy_from_slice(&bytes[offset..offset + buffer.len()]);
+                    return Ok(());
+                }
+            }
+            Err(MemoryReaderError::MemoryReadingError(format!(
+                "0x{:X} is not mapped",
+                address
+            )))
+        }
+    }
+
+    /// Bytes of a `TSparseArray` with `count` slots. The flags are inline
+    /// unless `secondary_data` is set.
+    fn sparse_array(count: u32, inline_word: u32, secondary_data: u64) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(DATA as u64).to_le_bytes());
+        bytes.extend_from_slice(&count.to_le_bytes());
+        bytes.extend_from_slice(&count.to_le_bytes());
+        bytes.extend_from_slice(&inline_word.to_le_bytes());
+        bytes.extend_from_slice(&[0; 12]);
+        bytes.extend_from_slice(&secondary_data.to_le_bytes());
+        bytes.extend_from_slice(&(count as i32).to_le_bytes());
+        bytes.extend_from_slice(&(count as i32).to_le_bytes());
+        bytes.extend_from_slice(&(-1i32).to_le_bytes());
+        bytes.extend_from_slice(&0i32.to_le_bytes());
+        assert_eq!(bytes.len(), size_of::<TSparseArrayHeader>());
+        bytes
+    }
+
+    /// A slot of `element_size` bytes starting with `value`, the rest is
+    /// filled with garbage like the hash ids of a real set.
+    fn slot(value: &[u8], element_size: usize) -> Vec<u8> {
+        let mut bytes = vec![0xCD; element_size];
+        bytes[..value.len()].copy_from_slice(value);
+        bytes
+    }
+
+    #[test]
+    fn read_set_skips_free_slots() {
+        let data = [1u32, 2, 3, 4]
+            .iter()
+            .flat_map(|v| slot(&v.to_le_bytes(), 12))
+            .collect();
+        let memory = FakeMemory::default()
+            .with(HEADER, sparse_array(4, 0b1011, 0))
+            .with(DATA, data);
+
+        let set = read_set::<u32, _>(&memory, HEADER).unwrap();
+        assert_eq!(set.into_vec(), vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn read_map_reads_values_after_the_key_padding() {
+        let data = [(1u32, 10u64), (2, 20), (3, 30)]
+            .iter()
+            .flat_map(|(k, v)| {
+                let mut pair = slot(&k.to_le_bytes(), 8);
+                pair.extend_from_slice(&v.to_le_bytes());
+                slot(&pair, 24)
+            })
+            .collect();
+        let memory = FakeMemory::default()
+            .with(HEADER, sparse_array(3, 0b110, 0))
+            .with(DATA, data);
+
+        let map = read_map::<u32, u64, _>(&memory, HEADER).unwrap();
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.into_vec(), vec![(2, 20), (3, 30)]);
+    }
+
+    #[test]
+    fn read_sparse_array_follows_secondary_flags() {
+        let count = 40;
+        let data = (0..count)
+            .flat_map(|v: u32| slot(&v.to_le_bytes(), 12))
+            .collect();
+        let mut words = Vec::new();
+        words.extend_from_slice(&1u32.to_le_bytes());
+        words.extend_from_slice(&0b1000_0001u32.to_le_bytes());
+        let memory = FakeMemory::default()
+            .with(HEADER, sparse_array(count, 0xFFFF_FFFF, FLAGS as u64))
+            .with(DATA, data)
+            .with(FLAGS, words);
+
+        let set = read_set::<u32, _>(&memory, HEADER).unwrap();
+        assert_eq!(set.into_vec(), vec![0, 32, 39]);
+    }
+
+    #[test]
+    fn read_sparse_array_rejects_more_slots_than_inline_bits() {
+        let memory = FakeMemory::default().with(HEADER, sparse_array(200, 0b1, 0));
+
+        assert!(matches!(
+            read_sparse_array(&memory, HEADER),
+            Err(MemoryReaderError::InvalidArray(_))
+        ));
+    }
+
+    #[test]
+    fn read_map_sized_points_at_allocated_pairs() {
+        let memory = FakeMemory::default().with(HEADER, sparse_array(3, 0b101, 0));
+
+        let map = read_map_sized(&memory, HEADER, 0x20, 0x10).unwrap();
+        let pointers = map
+            .iter()
+            .map(|v| (v.key_pointer.address(), v.value_pointer.address()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pointers,
+            vec![(DATA, DATA + 0x10), (DATA + 0x40, DATA + 0x50)]
+        );
     }
 }
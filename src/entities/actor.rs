@@ -1,12 +1,131 @@
+use crate::{
+    core::{
+        context::ReaderContext,
+        reader::{ActorInfo, MemoryReaderError},
+    },
+    services::sdk::{SdkResolved, SdkService},
+    structs::{
+        remote_ptr::RemotePtr,
+        unreal::{FRotator, FTransform, FVector, UObject},
+    },
+};
+
+const ROOT_COMPONENT: &str = "Actor.RootComponent";
+const RELATIVE_LOCATION: &str = "SceneComponent.RelativeLocation";
+const RELATIVE_ROTATION: &str = "SceneComponent.RelativeRotation";
+const COMPONENT_VELOCITY: &str = "SceneComponent.ComponentVelocity";
+const COMPONENT_TO_WORLD: &str = "SceneComponent.ComponentToWorld";
+
+/// SDK offsets used by `Actor`, resolved again whenever the SDK is reloaded.
+/// A missing member only fails the reads that need it.
+struct ActorOffsets {
+    root_component: Option<u32>,
+    relative_location: Option<u32>,
+    relative_rotation: Option<u32>,
+    component_velocity: Option<u32>,
+    /// Not a UPROPERTY, so only present in some SDK dumps.
+    component_to_world: Option<u32>,
+}
+
+impl ActorOffsets {
+    fn resolve(sdk: &SdkService) -> Self {
+        Self {
+            root_component: sdk.try_get_offset(ROOT_COMPONENT),
+            relative_location: sdk.try_get_offset(RELATIVE_LOCATION),
+            relative_rotation: sdk.try_get_offset(RELATIVE_ROTATION),
+            component_velocity: sdk.try_get_offset(COMPONENT_VELOCITY),
+            component_to_world: sdk.try_get_offset(COMPONENT_TO_WORLD),
+        }
+    }
+}
+
+fn required(offset: Option<u32>, attribute_path: &str) -> Result<u32, MemoryReaderError> {
+    offset.ok_or_else(|| {
+        MemoryReaderError::UnknownType(format!(
+            "Class or Struct attribute \"{}\" does not exist",
+            attribute_path
+        ))
+    })
+}
+
 pub struct Actor {
-    id: u32,
-    raw_name: String,
-    base_address: usize,
-    sdk_service: SdkService,
+    pub info: ActorInfo,
+    offsets: SdkResolved<ActorOffsets>,
+    /// Read on first use, an actor keeps its root component once spawned.
+    root_component: Option<RemotePtr<UObject>>,
 }
 
 impl Actor {
-    fn get_coordinates() {
-        
+    pub fn new(ctx: &ReaderContext, info: ActorInfo) -> Self {
+        Self {
+            info,
+            offsets: SdkResolved::new(ctx.sdk(), ActorOffsets::resolve),
+            root_component: None,
+        }
+    }
+
+    fn offsets(&mut self, ctx: &ReaderContext) -> &ActorOffsets {
+        self.offsets.get(ctx.sdk())
+    }
+
+    fn root_component(
+        &mut self,
+        ctx: &ReaderContext,
+    ) -> Result<RemotePtr<UObject>, MemoryReaderError> {
+        if let Some(root_component) = self.root_component {
+            return Ok(root_component);
+        }
+        let offset = required(self.offsets(ctx).root_component, ROOT_COMPONENT)?;
+        let root_component = self
+            .info
+            .base_address
+            .field::<RemotePtr<UObject>>(offset)
+            .read(ctx)?;
+        if !root_component.is_valid() {
+            return Err(MemoryReaderError::MemoryReadingError(format!(
+                "{} has no root component",
+                self.info.raw_name
+            )));
+        }
+        self.root_component = Some(root_component);
+        Ok(root_component)
+    }
+
+    fn component_to_world(
+        &mut self,
+        ctx: &ReaderContext,
+    ) -> Result<Option<FTransform>, MemoryReaderError> {
+        let offset = match self.offsets(ctx).component_to_world {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        let root_component = self.root_component(ctx)?;
+        Ok(Some(root_component.field::<FTransform>(offset).read(ctx)?))
+    }
+
+    /// World location of the actor, from `ComponentToWorld` when the SDK has it
+    /// and from the root component relative location otherwise.
+    pub fn get_coordinates(&mut self, ctx: &ReaderContext) -> Result<FVector, MemoryReaderError> {
+        if let Some(transform) = self.component_to_world(ctx)? {
+            return Ok(transform.translation);
+        }
+        let offset = required(self.offsets(ctx).relative_location, RELATIVE_LOCATION)?;
+        let root_component = self.root_component(ctx)?;
+        root_component.field::<FVector>(offset).read(ctx)
+    }
+
+    pub fn get_rotation(&mut self, ctx: &ReaderContext) -> Result<FRotator, MemoryReaderError> {
+        if let Some(transform) = self.component_to_world(ctx)? {
+            return Ok(transform.rotation.to_rotator());
+        }
+        let offset = required(self.offsets(ctx).relative_rotation, RELATIVE_ROTATION)?;
+        let root_component = self.root_component(ctx)?;
+        root_component.field::<FRotator>(offset).read(ctx)
+    }
+
+    pub fn get_velocity(&mut self, ctx: &ReaderContext) -> Result<FVector, MemoryReaderError> {
+        let offset = required(self.offsets(ctx).component_velocity, COMPONENT_VELOCITY)?;
+        let root_component = self.root_component(ctx)?;
+        root_component.field::<FVector>(offset).read(ctx)
     }
 }
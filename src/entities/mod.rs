@@ -1,2 +1,3 @@
+pub mod actor;
 pub mod ship;
 pub mod world;
@@ -1,109 +1,143 @@
-use std::{collections::HashMap, ffi::c_void};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    core::reader::{read_array, read_array_sized, read_pointer, ActorInfo},
-    services::sdk::sdk_service,
+    core::{
+        context::ReaderContext,
+        reader::{
+            find_dma_addy, read_array_sized, validate_array_header, ActorInfo, MemoryReaderError,
+        },
+    },
+    services::sdk::{SdkResolved, SdkService},
+    structs::{pod::Pod, remote_struct::RemoteStruct, tarray::TArrayHeader},
 };
 
+/// Actor name of each emissary table, with the faction whose ships it counts.
+const EMISSARY_TABLES: [(&str, &str); 6] = [
+    ("BP_EmissaryTable_GoldHoarders_01", "Gold Hoarders"),
+    ("BP_EmissaryTable_MerchantAlliance_01", "Merchants"),
+    ("BP_EmissaryTable_OrderOfSouls_01", "Order of Souls"),
+    ("BP_EmissaryTable_Sov_01_a_C", "Sovereign"),
+    ("BP_FactionEmissaryTable_Reapers2", "Reaper"),
+    ("BP_FactionEmissaryTable_Athena", "Athena"),
+];
+
 pub struct EmmissaryTables {
-    pub athena_emissary_table: Option<ActorInfo>,
-    pub reaper_emissary_table: Option<ActorInfo>,
-    pub sovereign_emissary_table: Option<ActorInfo>,
-    pub merchant_alliance_emissary_table: Option<ActorInfo>,
-    pub gold_hoarders_emissary_table: Option<ActorInfo>,
-    pub order_of_souls_emissary_table: Option<ActorInfo>,
+    /// Faction and actor of the loaded tables, in `EMISSARY_TABLES` order.
+    tables: Vec<(&'static str, ActorInfo)>,
 }
 
 impl EmmissaryTables {
-    pub fn get_mapping(&self) -> Vec<(&'static str, &Option<ActorInfo>)> {
-        vec![
-            (
-                "BP_EmissaryTable_GoldHoarders_01",
-                &self.gold_hoarders_emissary_table,
-            ),
-            (
-                "BP_EmissaryTable_MerchantAlliance_01",
-                &self.merchant_alliance_emissary_table,
-            ),
-            (
-                "BP_EmissaryTable_OrderOfSouls_01",
-                &self.gold_hoarders_emissary_table,
-            ),
-            (
-                "BP_EmissaryTable_Sov_01_a_C",
-                &self.sovereign_emissary_table,
-            ),
-            (
-                "BP_FactionEmissaryTable_Reapers2",
-                &self.reaper_emissary_table,
-            ),
-            (
-                "BP_FactionEmissaryTable_Athena",
-                &self.athena_emissary_table,
-            ),
-        ]
+    pub fn new() -> Self {
+        Self { tables: vec![] }
+    }
+
+    /// Finds the tables among the actors of the world.
+    pub fn update_actors<'a>(&mut self, actors: impl Iterator<Item = &'a ActorInfo>) {
+        let mut tables = actors
+            .filter_map(|actor| {
+                let index = EMISSARY_TABLES
+                    .iter()
+                    .position(|(actor_name, _)| *actor_name == actor.raw_name)?;
+                Some((index, actor.clone()))
+            })
+            .collect::<Vec<(usize, ActorInfo)>>();
+        tables.sort_by_key(|(index, _)| *index);
+        self.tables = tables
+            .into_iter()
+            .map(|(index, actor)| (EMISSARY_TABLES[index].1, actor))
+            .collect();
+    }
+
+    fn read_emissary_count(
+        ctx: &ReaderContext,
+        table: &ActorInfo,
+    ) -> Result<u32, MemoryReaderError> {
+        let class_name = ctx.read_class_name(table.base_address)?;
+        let sdk = ctx.sdk().get();
+        let attribute_path = format!("{}.EmissaryShipAffiliationTracker", class_name);
+        let missing = |path: &str| {
+            MemoryReaderError::UnknownType(format!(
+                "Class or Struct attribute \"{}\" does not exist",
+                path
+            ))
+        };
+        let tracker_offset = sdk
+            .try_get_offset(&attribute_path)
+            .ok_or_else(|| missing(&attribute_path))?;
+        let count_path = "EmissaryShipAffiliationTrackerComponent.EmissaryCount";
+        let count_offset = sdk
+            .try_get_offset(count_path)
+            .ok_or_else(|| missing(count_path))?;
+
+        find_dma_addy::<u32, _>(
+            ctx,
+            table.base_address.address(),
+            vec![tracker_offset, count_offset],
+        )
+    }
+
+    /// Number of emissary ships of each faction whose table is loaded, or why
+    /// it could not be read.
+    pub fn read_counts(
+        &self,
+        ctx: &ReaderContext,
+    ) -> Vec<(&'static str, Result<u32, MemoryReaderError>)> {
+        self.tables
+            .iter()
+            .map(|(faction, table)| (*faction, Self::read_emissary_count(ctx, table)))
+            .collect()
     }
 }
 
-impl EmmissaryTables {
-    fn new() -> Self {
-        Self {
-            athena_emissary_table: None,
-            reaper_emissary_table: None,
-            sovereign_emissary_table: None,
-            merchant_alliance_emissary_table: None,
-            gold_hoarders_emissary_table: None,
-            order_of_souls_emissary_table: None,
-        }
+/// SDK offsets used by `CrewService`, resolved again whenever the SDK is reloaded.
+struct CrewOffsets {
+    crews: u32,
+    crew_size: u32,
+    /// Only used to report where a bad player array was read.
+    players: u32,
+}
+
+impl CrewOffsets {
+    /// Fails when a reloaded SDK lacks one of the entries, until the next
+    /// reload brings it back.
+    fn resolve(sdk: &SdkService) -> Result<Self, MemoryReaderError> {
+        let offset = |path: &str| {
+            sdk.try_get_offset(path).ok_or_else(|| {
+                MemoryReaderError::UnknownType(format!(
+                    "Class or Struct attribute \"{}\" does not exist",
+                    path
+                ))
+            })
+        };
+        Ok(Self {
+            crews: offset("CrewService.Crews")?,
+            players: offset("Crew.Players")?,
+            crew_size: sdk.try_get_class_or_struct_size("Crew").ok_or_else(|| {
+                MemoryReaderError::UnknownType("Class or Struct \"Crew\" does not exist".into())
+            })?,
+        })
     }
+}
 
-    // fn update(&self) {
-    //     for (table_actor, emissary_label) in vec![
-    //         (&self.gold_hoarders_emissary_table, "Gold Hoarders : "),
-    //         (&self.merchant_alliance_emissary_table, "Merchants : "),
-    //         (&self.order_of_souls_emissary_table, "Order of souls : "),
-    //         (&self.sovereign_emissary_table, "Sovereign : "),
-    //         (&self.reaper_emissary_table, "Reaper : "),
-    //         (&self.athena_emissary_table, "Athena : "),
-    //     ] {
-    //         if table_actor.is_none() {
-    //             continue;
-    //         }
-    //         let table_actor_info = table_actor.clone().unwrap();
-    //         let u_object = read_pointer(table_actor_info.base_address as *mut UObject).unwrap();
-
-    //         let class_ = read_pointer(u_object.u_class).unwrap();
-    //         let class_name = read_gname(class_.name.index).unwrap();
-
-    //         let emissary_ship_affiliation_tracker_offset =
-    //             sdk_service().get_offset(&format!("{}.EmissaryShipAffiliationTracker", class_name));
-
-    //         let emissary_count_offset =
-    //             sdk_service().get_offset("EmissaryShipAffiliationTrackerComponent.EmissaryCount");
-
-    //         let emmisary_count = find_dma_addy::<u32>(
-    //             table_actor_info.base_address,
-    //             vec![
-    //                 emissary_ship_affiliation_tracker_offset,
-    //                 emissary_count_offset,
-    //             ],
-    //         )
-    //         .unwrap();
-
-    //         println!("{} {}", emissary_label, emmisary_count);
-    //     }
-    // }
+#[derive(RemoteStruct)]
+struct CrewView {
+    #[sdk("Crew.CrewId")]
+    crew_id: Guid,
+    #[sdk("Crew.Players")]
+    players: TArrayHeader,
 }
 
 pub struct CrewService {
     actor: ActorInfo,
-    crews: HashMap<Guid, u32>,
+    offsets: SdkResolved<Result<CrewOffsets, MemoryReaderError>>,
+    crews: Vec<(Guid, u32)>,
     my_crew_id: Option<Guid>,
     total_players: u32,
 }
 
 #[allow(non_snake_case)]
-#[derive(Eq, Hash, PartialEq)]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub struct Guid {
     pub A: u32,
     pub B: u32,
@@ -111,59 +145,74 @@ pub struct Guid {
     pub D: u32,
 }
 
+unsafe impl Pod for Guid {}
+
 impl CrewService {
-    pub fn new(actor: ActorInfo) -> Self {
+    pub fn new(ctx: &ReaderContext, actor: ActorInfo) -> Self {
         Self {
-            actor: actor,
-            crews: HashMap::new(),
+            actor,
+            offsets: SdkResolved::new(ctx.sdk(), CrewOffsets::resolve),
+            crews: vec![],
             my_crew_id: None,
             total_players: 0,
         }
     }
 
-    fn get_crews(&self) -> HashMap<Guid, u32> {
+    fn get_crews(&mut self, ctx: &ReaderContext) -> Result<Vec<(Guid, u32)>, MemoryReaderError> {
+        let offsets = self.offsets.get(ctx.sdk()).as_ref().map_err(Clone::clone)?;
         let crew_array = read_array_sized(
-            &self.actor.base_address + sdk_service().get_offset("CrewService.Crews") as usize,
-            sdk_service().get_class_or_struct_size("Crew") as usize,
-        );
+            ctx,
+            self.actor.base_address.address() + offsets.crews as usize,
+            offsets.crew_size as usize,
+        )?;
 
-        let mut crews_hasmap: HashMap<Guid, u32> = HashMap::new();
+        let mut crews = vec![];
 
-        let crew_guid_offset = sdk_service().get_offset("Crew.CrewId");
-        let player_array_offset = sdk_service().get_offset("Crew.Players");
         for crew_actor_pointer in crew_array.iter() {
-            let crew_base = crew_actor_pointer.item_pointer as usize;
-            let crew_guid =
-                read_pointer((crew_base + crew_guid_offset as usize) as *mut Guid).unwrap();
-
-            let crew_player_array = read_array::<c_void>(crew_base + player_array_offset as usize);
-            crews_hasmap.insert(crew_guid, crew_player_array.count);
+            let crew_base = crew_actor_pointer.item_pointer.cast::<CrewView>();
+            let crew = crew_base.read_struct(ctx)?;
+
+            let crew_player_array = validate_array_header(
+                ctx,
+                crew_base.address() + offsets.players as usize,
+                crew.players,
+            )?;
+            crews.push((crew.crew_id, crew_player_array.count));
         }
-        crews_hasmap
+        Ok(crews)
     }
 
     fn get_total_players(&self) -> u32 {
-        self.crews.values().sum()
+        self.crews.iter().map(|(_, players)| players).sum()
     }
 
-    fn is_valid(&self) -> bool {
-        read_pointer(self.actor.base_address as *mut c_void).is_err()
+    fn is_valid(&self, ctx: &ReaderContext) -> bool {
+        self.actor.base_address.cast::<u64>().read(ctx).is_err()
     }
 
-    pub fn update(&mut self) {
-        // if !self.is_valid() {
+    /// Reads the crews again. They are left as they were if that fails.
+    pub fn update(&mut self, ctx: &ReaderContext) -> Result<(), MemoryReaderError> {
+        // if !self.is_valid(ctx) {
         //     println!("CrewService is not valid");
         //     return;
         // }
-        self.crews = self.get_crews();
-        self.total_players = self.get_total_players()
+        self.crews = self.get_crews(ctx)?;
+        self.total_players = self.get_total_players();
+        Ok(())
     }
 
-    pub fn print_crews(&self) {
-        for (index, crew_player_count) in self.crews.values().enumerate() {
-            println!("Crew {}: {} players", index, crew_player_count);
-        }
-        println!("Total players: {}", self.total_players);
+    pub fn actor(&self) -> &ActorInfo {
+        &self.actor
+    }
+
+    /// Player count of every crew, as of the last `update`, in the order of
+    /// the `Crews` array so that it does not change from one read to the next.
+    pub fn crews(&self) -> &[(Guid, u32)] {
+        &self.crews
+    }
+
+    pub fn total_players(&self) -> u32 {
+        self.total_players
     }
 }
 
@@ -181,4 +230,11 @@ impl World {
             emissary_tables: EmmissaryTables::new(),
         }
     }
+
+    pub fn update(&mut self, ctx: &ReaderContext) -> Result<(), MemoryReaderError> {
+        match self.crew_service.as_mut() {
+            Some(crew_service) => crew_service.update(ctx),
+            None => Ok(()),
+        }
+    }
 }
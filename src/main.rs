@@ -1,3 +1,4 @@
+mod cli;
 mod core;
 mod entities;
 mod services;
@@ -7,11 +8,30 @@ use ggez::{event, ContextBuilder};
 use services::event_loop as my_event_loop;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (target, args) = match cli::target::parse_target(&args) {
+        Ok(v) => v,
+        Err(v) => return println!("{}", v),
+    };
+    let (tick_rate, args) = match cli::parse_tick_rate(&args) {
+        Ok(v) => v,
+        Err(v) => return println!("{}", v),
+    };
+    let (max_array_count, args) = match cli::parse_max_array_count(&args) {
+        Ok(v) => v,
+        Err(v) => return println!("{}", v),
+    };
+    if !args.is_empty() {
+        return cli::run(&args, target, tick_rate, max_array_count);
+    }
+    let preferred_pid = cli::target::select_process(&target);
+
     let (mut ctx, event_loop) = ContextBuilder::new("sot_reader", "Sot Reader")
         .build()
         .expect("aieee, could not create ggez context!");
 
-    let my_game = my_event_loop::MyGame::new(&mut ctx);
+    let my_game =
+        my_event_loop::MyGame::new(&mut ctx, target, preferred_pid, tick_rate, max_array_count);
 
     event::run(ctx, event_loop, my_game);
 }
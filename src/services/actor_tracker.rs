@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use crate::core::reader::{ActorInfo, ActorKey};
+
+#[derive(Debug, Clone)]
+pub enum ActorEvent {
+    Spawned(ActorInfo),
+    Despawned(ActorInfo),
+    /// The only actor of a name went away and another one of the same name
+    /// appeared elsewhere in the same tick, e.g. `CrewService` after a reload.
+    AddressChanged {
+        old: ActorInfo,
+        new: ActorInfo,
+    },
+}
+
+/// Actors seen on the last tick, diffed against each new enumeration.
+pub struct ActorTracker {
+    actors: HashMap<ActorKey, ActorInfo>,
+}
+
+impl ActorTracker {
+    pub fn new() -> Self {
+        Self {
+            actors: HashMap::new(),
+        }
+    }
+
+    /// Replaces the tracked actors with `actors` and returns what changed.
+    pub fn update(&mut self, actors: HashMap<ActorKey, ActorInfo>) -> Vec<ActorEvent> {
+        let mut despawned = HashMap::<u32, Vec<ActorInfo>>::new();
+        for (key, actor) in &self.actors {
+            if !actors.contains_key(key) {
+                despawned
+                    .entry(key.name_id)
+                    .or_default()
+                    .push(actor.clone());
+            }
+        }
+        let mut spawned = HashMap::<u32, Vec<ActorInfo>>::new();
+        for (key, actor) in &actors {
+            if !self.actors.contains_key(key) {
+                spawned.entry(key.name_id).or_default().push(actor.clone());
+            }
+        }
+        self.actors = actors;
+
+        let mut events = vec![];
+        for (name_id, new) in spawned {
+            match despawned.remove(&name_id) {
+                Some(old) if old.len() == 1 && new.len() == 1 => {
+                    events.push(ActorEvent::AddressChanged {
+                        old: old.into_iter().next().unwrap(),
+                        new: new.into_iter().next().unwrap(),
+                    });
+                }
+                old => {
+                    events.extend(old.into_iter().flatten().map(ActorEvent::Despawned));
+                    events.extend(new.into_iter().map(ActorEvent::Spawned));
+                }
+            }
+        }
+        events.extend(despawned.into_values().flatten().map(ActorEvent::Despawned));
+        events
+    }
+
+    pub fn actors(&self) -> impl Iterator<Item = &ActorInfo> {
+        self.actors.values()
+    }
+
+    pub fn find_by_name<'a>(&'a self, raw_name: &'a str) -> impl Iterator<Item = &'a ActorInfo> {
+        self.actors.values().filter(move |v| v.raw_name == raw_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::remote_ptr::RemotePtr;
+
+    fn actor(id: u32, raw_name: &str, address: usize) -> ActorInfo {
+        ActorInfo {
+            id,
+            raw_name: raw_name.to_string(),
+            base_address: RemotePtr::new(address),
+        }
+    }
+
+    fn actors(list: &[ActorInfo]) -> HashMap<ActorKey, ActorInfo> {
+        list.iter().map(|v| (v.key(), v.clone())).collect()
+    }
+
+    #[test]
+    fn reports_spawned_and_despawned_actors() {
+        let ship = actor(1, "BP_Ship", 0x1000);
+        let island = actor(2, "BP_Island", 0x2000);
+        let mut tracker = ActorTracker::new();
+
+        let events = tracker.update(actors(&[ship.clone()]));
+        assert!(matches!(events.as_slice(), [ActorEvent::Spawned(v)] if v.key() == ship.key()));
+
+        let events = tracker.update(actors(&[island.clone()]));
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .any(|v| matches!(v, ActorEvent::Spawned(v) if v.key() == island.key())));
+        assert!(events
+            .iter()
+            .any(|v| matches!(v, ActorEvent::Despawned(v) if v.key() == ship.key())));
+
+        assert!(tracker.update(actors(&[island])).is_empty());
+        assert!(matches!(
+            tracker.update(HashMap::new()).as_slice(),
+            [ActorEvent::Despawned(_)]
+        ));
+    }
+
+    #[test]
+    fn reports_a_moved_singleton_as_an_address_change() {
+        let old = actor(3, "CrewService", 0x1000);
+        let new = actor(3, "CrewService", 0x3000);
+        let mut tracker = ActorTracker::new();
+        tracker.update(actors(&[old.clone()]));
+
+        let events = tracker.update(actors(&[new.clone()]));
+        assert!(matches!(
+            events.as_slice(),
+            [ActorEvent::AddressChanged { old: o, new: n }]
+                if o.key() == old.key() && n.key() == new.key()
+        ));
+    }
+
+    #[test]
+    fn does_not_pair_actors_when_several_share_a_name() {
+        let mut tracker = ActorTracker::new();
+        tracker.update(actors(&[
+            actor(4, "BP_Barrel", 0x1000),
+            actor(4, "BP_Barrel", 0x2000),
+        ]));
+
+        let events = tracker.update(actors(&[actor(4, "BP_Barrel", 0x3000)]));
+        let spawned = events
+            .iter()
+            .filter(|v| matches!(v, ActorEvent::Spawned(_)))
+            .count();
+        let despawned = events
+            .iter()
+            .filter(|v| matches!(v, ActorEvent::Despawned(_)))
+            .count();
+        assert_eq!((spawned, despawned), (1, 2));
+    }
+}
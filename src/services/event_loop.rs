@@ -1,72 +1,59 @@
-use std::collections::HashMap;
-
-use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use ggez::event::EventHandler;
-use ggez::graphics::{self, Color};
+use ggez::graphics::{self, Color, DrawParam, Text};
 use ggez::{Context, GameResult};
 
-use crate::core::reader::{ActorInfo, SoTMemoryReader};
-use crate::entities::world::{CrewService, World};
+use crate::core::reader::ProcessTarget;
+use crate::services::reader_thread::ReaderThread;
+use crate::services::sdk::SdkHandle;
+use crate::services::world_reader::WorldReader;
 
+/// Only draws the latest snapshot, all the reading happens in `ReaderThread`.
 pub struct MyGame {
-    sot_memory_reader: Arc<Mutex<SoTMemoryReader>>,
-    actors_map: HashMap<u32, ActorInfo>,
-    player_in_game: bool,
-    world: Option<World>,
+    reader: ReaderThread,
 }
 
 impl MyGame {
-    pub fn new(_ctx: &mut Context) -> MyGame {
-        Arc::new(SoTMemoryReader::new("SoTGame.exe").unwrap());
-        let sot_memory_reader = SoTMemoryReader::new("SoTGame.exe").unwrap();
-        let mutex_reader = Mutex::new(sot_memory_reader);
-        let arc_reader = Arc::new(mutex_reader);
+    pub fn new(
+        _ctx: &mut Context,
+        target: ProcessTarget,
+        preferred_pid: Option<u32>,
+        tick_rate: f64,
+        max_array_count: u32,
+    ) -> MyGame {
+        let sdk = SdkHandle::load_default();
+        sdk.watch(Duration::from_secs(1));
+        let mut reader = WorldReader::new(target, sdk, max_array_count);
+        reader.prefer_pid(preferred_pid);
 
         MyGame {
-            sot_memory_reader: arc_reader.clone(),
-            actors_map: HashMap::new(),
-            player_in_game: false,
-            world: None,
+            reader: ReaderThread::spawn(reader, tick_rate),
         }
     }
 }
 
 impl EventHandler for MyGame {
     fn update(&mut self, _ctx: &mut Context) -> GameResult {
-        print!("\x1B[2J\x1B[1;1H");
-        let mut reader = self.sot_memory_reader.lock().unwrap();
-        let _ = reader.read_actors(&mut self.actors_map);
-
-        let mut world = World::new();
-
-        for (_actor_id, actor_info) in &self.actors_map {
-            if actor_info.raw_name == "CrewService" {
-                world.crew_service = Some(CrewService::new(actor_info.clone()));
-                continue;
-            }
-        }
-        if world.crew_service.is_none() {
-            self.player_in_game = false;
-        } else {
-            self.world = Some(world);
-        }
-
         Ok(())
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        let canvas = graphics::Canvas::from_frame(ctx, Color::WHITE);
-        if *&self.world.is_none() {
-            println!("player is not ingame");
-        }
-        let world = self.world.as_mut().unwrap();
-        let crew_service = world.crew_service.as_mut().unwrap();
-        crew_service.update();
+        let mut canvas = graphics::Canvas::from_frame(ctx, Color::WHITE);
+        let lines = match self.reader.stopped() {
+            Some(reason) => vec![format!("Reader stopped: {}", reason)],
+            None => self.reader.latest().status_lines(),
+        };
+
         print!("\x1B[2J\x1B[1;1H");
-        crew_service.print_crews();
+        for line in &lines {
+            println!("{}", line);
+        }
+        canvas.draw(
+            &Text::new(lines.join("\n")),
+            DrawParam::default().dest([10.0, 10.0]).color(Color::BLACK),
+        );
 
-        // Draw code here...
         canvas.finish(ctx)
     }
 }
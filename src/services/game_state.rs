@@ -0,0 +1,182 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Levels loaded while in the front end, before joining a server.
+const MAIN_MENU_LEVELS: [&str; 2] = ["MainMenu", "FrontEnd"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameState {
+    ProcessNotRunning,
+    /// The game runs but we could not attach to it or resolve its signatures yet.
+    Attaching,
+    MainMenu,
+    /// No world, or a world without the in-game singletons yet.
+    Loading,
+    InGame,
+    /// Left a server without going through the menu, e.g. kicked or timed out.
+    Disconnected,
+}
+
+impl fmt::Display for GameState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            GameState::ProcessNotRunning => "Waiting for the game to start",
+            GameState::Attaching => "Attaching to the game",
+            GameState::MainMenu => "In the main menu",
+            GameState::Loading => "Loading",
+            GameState::InGame => "In game",
+            GameState::Disconnected => "Disconnected from the server",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameStateTransition {
+    pub from: GameState,
+    pub to: GameState,
+}
+
+/// What the reader saw on one tick, the input of `GameSession::update`.
+#[derive(Debug, Clone, Default)]
+pub struct GameObservation {
+    pub process_running: bool,
+    pub attached: bool,
+    /// A UWorld is loaded and has at least one level.
+    pub world_loaded: bool,
+    pub level_names: Vec<String>,
+    pub has_crew_service: bool,
+}
+
+impl GameObservation {
+    fn in_main_menu(&self) -> bool {
+        self.level_names.iter().any(|name| {
+            MAIN_MENU_LEVELS
+                .iter()
+                .any(|v| name.to_lowercase().contains(&v.to_lowercase()))
+        })
+    }
+}
+
+pub struct GameSession {
+    state: GameState,
+}
+
+impl GameSession {
+    pub fn new() -> Self {
+        Self {
+            state: GameState::ProcessNotRunning,
+        }
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// Moves to the state matching `observation`, returns the transition if
+    /// the state changed.
+    pub fn update(&mut self, observation: &GameObservation) -> Option<GameStateTransition> {
+        let next = next_state(self.state, observation);
+        if next == self.state {
+            return None;
+        }
+        let transition = GameStateTransition {
+            from: self.state,
+            to: next,
+        };
+        self.state = next;
+        Some(transition)
+    }
+}
+
+fn next_state(current: GameState, observation: &GameObservation) -> GameState {
+    if !observation.process_running {
+        return GameState::ProcessNotRunning;
+    }
+    if !observation.attached {
+        return GameState::Attaching;
+    }
+    if !observation.world_loaded {
+        return GameState::Loading;
+    }
+    if observation.in_main_menu() {
+        return GameState::MainMenu;
+    }
+    if observation.has_crew_service {
+        return GameState::InGame;
+    }
+    // A world without a `CrewService` is either one being loaded or what is
+    // left after the server dropped us.
+    match current {
+        GameState::InGame | GameState::Disconnected => GameState::Disconnected,
+        _ => GameState::Loading,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn in_world(level: &str, has_crew_service: bool) -> GameObservation {
+        GameObservation {
+            process_running: true,
+            attached: true,
+            world_loaded: true,
+            level_names: vec![level.to_string()],
+            has_crew_service,
+        }
+    }
+
+    #[test]
+    fn losing_the_crew_service_in_game_is_a_disconnect() {
+        let observation = in_world("BP_SeaOfThieves", false);
+        assert_eq!(
+            next_state(GameState::InGame, &observation),
+            GameState::Disconnected
+        );
+        assert_eq!(
+            next_state(GameState::Disconnected, &observation),
+            GameState::Disconnected
+        );
+    }
+
+    #[test]
+    fn a_world_without_crew_service_is_loading_otherwise() {
+        let observation = in_world("BP_SeaOfThieves", false);
+        assert_eq!(
+            next_state(GameState::MainMenu, &observation),
+            GameState::Loading
+        );
+        assert_eq!(
+            next_state(GameState::Loading, &observation),
+            GameState::Loading
+        );
+    }
+
+    #[test]
+    fn front_end_levels_are_the_main_menu() {
+        for level in ["MainMenu", "Athena_FrontEnd", "mainmenu_p"] {
+            let observation = in_world(level, false);
+            assert_eq!(
+                next_state(GameState::Disconnected, &observation),
+                GameState::MainMenu
+            );
+        }
+        // The menu wins over a leftover crew service.
+        let observation = in_world("MainMenu", true);
+        assert_eq!(
+            next_state(GameState::InGame, &observation),
+            GameState::MainMenu
+        );
+    }
+
+    #[test]
+    fn reconnecting_leaves_the_disconnected_state() {
+        let observation = in_world("BP_SeaOfThieves", true);
+        assert_eq!(
+            next_state(GameState::Disconnected, &observation),
+            GameState::InGame
+        );
+    }
+}
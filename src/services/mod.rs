@@ -1,2 +1,12 @@
+pub mod actor_tracker;
 pub mod event_loop;
+pub mod game_state;
+pub mod process_watcher;
+pub mod reader_thread;
 pub mod sdk;
+pub mod sdk_diff;
+pub mod sdk_export;
+pub mod sdk_overrides;
+pub mod world_event;
+pub mod world_reader;
+pub mod world_snapshot;
@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+
+use crate::core::context::ReaderContext;
+use crate::core::reader::{find_processes, ProcessTarget, SoTMemoryReader};
+use crate::services::sdk::SdkHandle;
+
+/// Attaches to the game when it starts, and again whenever it restarts.
+pub struct ProcessWatcher {
+    target: ProcessTarget,
+    sdk: SdkHandle,
+    max_array_count: u32,
+    preferred_pid: Option<u32>,
+    interval: Duration,
+    last_poll: Option<Instant>,
+    process_running: bool,
+    /// Whether the ambiguous target was reported, which is only done once.
+    several_reported: bool,
+    reader: Option<SoTMemoryReader>,
+}
+
+impl ProcessWatcher {
+    pub fn new(
+        target: ProcessTarget,
+        sdk: SdkHandle,
+        max_array_count: u32,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            target,
+            sdk,
+            max_array_count,
+            preferred_pid: None,
+            interval,
+            last_poll: None,
+            process_running: false,
+            several_reported: false,
+            reader: None,
+        }
+    }
+
+    /// Attaches to `pid` when several processes match the target. Only used
+    /// until the first attach, later ones follow the target.
+    pub fn prefer_pid(&mut self, pid: Option<u32>) {
+        self.preferred_pid = pid;
+    }
+
+    /// Looks for the process at most once per `interval`. Attaching fails
+    /// while the game is still starting, it is retried on the next poll.
+    pub fn poll(&mut self) {
+        if self.last_poll.is_some_and(|v| v.elapsed() < self.interval) {
+            return;
+        }
+        self.last_poll = Some(Instant::now());
+
+        let processes = find_processes(&self.target);
+        self.process_running = !processes.is_empty();
+        let attached = self.reader.as_ref().and_then(|v| v.ctx().pid());
+        if self.reader.is_some() && processes.iter().any(|(pid, _)| Some(*pid) == attached) {
+            return;
+        }
+
+        if self.reader.take().is_some() {
+            eprintln!("Detached from {}", self.target);
+        }
+        let several = processes.len() > 1;
+        let pid = match processes.as_slice() {
+            [] => None,
+            [(pid, _)] => Some(*pid),
+            _ => self
+                .preferred_pid
+                .filter(|v| processes.iter().any(|(pid, _)| pid == v)),
+        };
+        let pid = match pid {
+            Some(v) => v,
+            None => {
+                if several && !self.several_reported {
+                    eprintln!(
+                        "Several {} processes are running, select one with --pid",
+                        self.target
+                    );
+                }
+                self.several_reported = several;
+                return;
+            }
+        };
+        self.several_reported = false;
+        match ReaderContext::attach(&ProcessTarget::Pid(pid), self.sdk.clone()) {
+            Ok(mut v) => {
+                eprintln!("Attached to {} ({})", self.target, pid);
+                v.set_max_array_count(self.max_array_count);
+                self.reader = Some(SoTMemoryReader::new(v));
+                self.preferred_pid = None;
+            }
+            Err(v) => {
+                eprintln!("Could not attach to {}: {:?}", self.target, v);
+            }
+        }
+    }
+
+    pub fn is_process_running(&self) -> bool {
+        self.process_running
+    }
+
+    pub fn reader(&mut self) -> Option<&mut SoTMemoryReader> {
+        self.reader.as_mut()
+    }
+}
@@ -0,0 +1,94 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use arc_swap::ArcSwap;
+
+use crate::services::world_reader::WorldReader;
+use crate::services::world_snapshot::WorldSnapshot;
+
+/// Reads the game on its own thread, `tick_rate` times per second, so that a
+/// slow read never stalls rendering. Stopped when dropped, or when a tick
+/// panics, see `stopped`.
+pub struct ReaderThread {
+    latest: Arc<ArcSwap<WorldSnapshot>>,
+    stop: Arc<AtomicBool>,
+    stopped: Arc<Mutex<Option<String>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ReaderThread {
+    /// Moves `reader` to the new thread. Subscribe to `reader` beforehand to
+    /// receive its events, see `WorldReader::subscribe`.
+    pub fn spawn(mut reader: WorldReader, tick_rate: f64) -> Self {
+        let latest = Arc::new(ArcSwap::from_pointee(WorldSnapshot::empty()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::new(Mutex::new(None));
+        let interval = Duration::from_secs_f64(1.0 / tick_rate);
+        let events = reader.event_bus();
+
+        let handle = {
+            let latest = latest.clone();
+            let stop = stop.clone();
+            let stopped = stopped.clone();
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let started = Instant::now();
+                    match panic::catch_unwind(AssertUnwindSafe(|| reader.tick())) {
+                        Ok(snapshot) => latest.store(Arc::new(snapshot)),
+                        Err(payload) => {
+                            *stopped.lock().unwrap() = Some(panic_message(&*payload));
+                            // Disconnects the subscribers instead of leaving
+                            // them waiting for events that never come.
+                            events.close();
+                            break;
+                        }
+                    }
+                    if let Some(v) = interval.checked_sub(started.elapsed()) {
+                        thread::sleep(v);
+                    }
+                }
+            })
+        };
+
+        Self {
+            latest,
+            stop,
+            stopped,
+            handle: Some(handle),
+        }
+    }
+
+    /// Snapshot of the last finished tick.
+    pub fn latest(&self) -> Arc<WorldSnapshot> {
+        self.latest.load_full()
+    }
+
+    /// Why the reader thread stopped, if a tick panicked. `latest` keeps
+    /// returning the last snapshot read before that.
+    pub fn stopped(&self) -> Option<String> {
+        self.stopped.lock().unwrap().clone()
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(v) = payload.downcast_ref::<&str>() {
+        v.to_string()
+    } else if let Some(v) = payload.downcast_ref::<String>() {
+        v.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+impl Drop for ReaderThread {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -1,11 +1,23 @@
 use std::collections::HashMap;
 use std::fs;
-use std::sync::OnceLock;
+use std::hash::Hasher;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, UNIX_EPOCH};
 
-use serde::de::{self, Deserializer};
-use serde::Deserialize;
+use arc_swap::ArcSwap;
 
-const FILE_WHITELIST: [&str; 10] = [
+use serde::de::{self, DeserializeOwned, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::services::sdk_overrides::{default_overrides_path, SdkOverrides};
+
+const FILE_WHITELIST: [&str; 12] = [
     "BP_EmissaryTable_01_Classes.json",
     "BP_EmissaryTable_GoldHoarder_01_Classes.json",
     "BP_EmissaryTable_MerchantAlliance_01_Classes.json",
@@ -16,12 +28,25 @@ const FILE_WHITELIST: [&str; 10] = [
     "EmissaryLevel_Classes.json",
     "Athena_Structs.json",
     "Athena_Classes.json",
+    "Engine_Classes.json",
+    "Engine_Structs.json",
 ];
 
+/// Binary cache of the parsed SDK, written next to the JSON files.
+const SDK_CACHE_FILE: &str = "sdk.cache";
+/// Bump whenever the layout of the cached structs changes.
+const SDK_CACHE_VERSION: u32 = 5;
+
+// Sizes, offsets and masks are hex strings in the JSON dumps and plain integers
+// in the binary cache, which is not human readable.
+
 fn deserialize_hex<'de, D>(deserializer: D) -> Result<u32, D::Error>
 where
     D: Deserializer<'de>,
 {
+    if !deserializer.is_human_readable() {
+        return u32::deserialize(deserializer);
+    }
     let s = String::deserialize(deserializer)?;
     if s.starts_with('-') {
         return Ok(0);
@@ -29,84 +54,453 @@ where
     u32::from_str_radix(&s[2..], 16).map_err(de::Error::custom)
 }
 
-#[derive(Deserialize)]
+fn serialize_hex<S>(value: &u32, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if !serializer.is_human_readable() {
+        return serializer.serialize_u32(*value);
+    }
+    serializer.serialize_str(&format!("0x{:X}", value))
+}
+
+fn deserialize_optional_hex<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        return Option::<u8>::deserialize(deserializer);
+    }
+    match Option::<String>::deserialize(deserializer)? {
+        Some(s) => {
+            let digits = s.trim_start_matches("0x").trim_start_matches("0X");
+            u8::from_str_radix(digits, 16)
+                .map(Some)
+                .map_err(de::Error::custom)
+        }
+        None => Ok(None),
+    }
+}
+
+fn serialize_optional_hex<S>(value: &Option<u8>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if !serializer.is_human_readable() {
+        return value.serialize(serializer);
+    }
+    match value {
+        Some(v) => serializer.serialize_some(&format!("0x{:X}", v)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// `true`/`false`, or a bit size such as `"0x1"` which only bitfields have.
+fn deserialize_bit_field<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        return bool::deserialize(deserializer);
+    }
+    match Value::deserialize(deserializer)? {
+        Value::Bool(v) => Ok(v),
+        Value::Number(_) | Value::String(_) => Ok(true),
+        Value::Null => Ok(false),
+        v => Err(de::Error::custom(format!("invalid bitfield marker {}", v))),
+    }
+}
+
+/// FNV-1a, whose output unlike `DefaultHasher` is the same across runs and
+/// Rust versions, so it can key a cache written to disk.
+struct StableHasher(u64);
+
+impl StableHasher {
+    fn new() -> Self {
+        Self(0xCBF2_9CE4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01B3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Adds the name, size and modification time of `path` to `hasher`.
+fn hash_file_metadata(hasher: &mut StableHasher, path: &Path) -> io::Result<()> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    if let Some(name) = path.file_name() {
+        hasher.write(name.to_string_lossy().as_bytes());
+    }
+    hasher.write_u64(metadata.len());
+    hasher.write_u64(modified.as_secs());
+    hasher.write_u32(modified.subsec_nanos());
+    Ok(())
+}
+
+/// UE packs `bool` flags into bitfields sharing one byte. Bools declared back
+/// to back at the same offset, or marked as bitfields by the dump, form one
+/// bitfield. Those without a mask from the dump get the free bits in
+/// declaration order.
+fn infer_bit_masks(attributes: &mut [SdkAttribute]) {
+    let mut start = 0;
+    while start < attributes.len() {
+        let offset = attributes[start].Offset;
+        let mut end = start;
+        while end < attributes.len()
+            && attributes[end].Offset == offset
+            && attributes[end].Type == "bool"
+            && attributes[end].Size == 1
+        {
+            end += 1;
+        }
+
+        let group = &mut attributes[start..end];
+        let is_bitfield =
+            group.len() > 1 || group.iter().any(|v| v.BitField || v.BitMask.is_some());
+        let used_bits = group.iter().filter_map(|v| v.BitMask).fold(0, |a, b| a | b);
+        let free_bits = (0..8)
+            .map(|bit| 1u8 << bit)
+            .filter(|mask| used_bits & mask == 0)
+            .collect::<Vec<u8>>();
+        let unmasked = group.iter().filter(|v| v.BitMask.is_none()).count();
+        if is_bitfield && unmasked <= free_bits.len() {
+            for (attribute, mask) in group
+                .iter_mut()
+                .filter(|v| v.BitMask.is_none())
+                .zip(free_bits)
+            {
+                attribute.BitMask = Some(mask);
+            }
+        }
+        start = end.max(start + 1);
+    }
+}
+
+/// Entries without `Attributes` are not classes or structs but metadata, such
+/// as the `"DougTheDruid"` credit key carried by the dumps.
+fn is_sdk_entry(value: &Value) -> bool {
+    value.get("Attributes").is_some()
+}
+
+fn parse_sdk_file<T: DeserializeOwned>(
+    content: &[u8],
+) -> serde_json::Result<(HashMap<String, T>, HashMap<String, String>)> {
+    let raw = serde_json::from_slice::<Map<String, Value>>(content)?;
+    let mut entries = HashMap::new();
+    let mut metadata = HashMap::new();
+
+    for (key, value) in raw {
+        if !is_sdk_entry(&value) {
+            let value = match value {
+                Value::String(v) => v,
+                v => v.to_string(),
+            };
+            metadata.insert(key, value);
+            continue;
+        }
+        match serde_json::from_value::<T>(value) {
+            Ok(v) => entries.insert(key, v),
+            Err(v) => return Err(de::Error::custom(format!("{}: {}", key, v))),
+        };
+    }
+    Ok((entries, metadata))
+}
+
+/// Whitelisted JSON files of `sdk_path`, sorted by name.
+fn sdk_dir_files(sdk_path: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = fs::read_dir(sdk_path)
+        .map_err(|v| format!("Directory: {} \n{}", sdk_path.display(), v))?
+        .filter_map(Result::ok)
+        .filter(|path| match path.file_name().into_string() {
+            Ok(file_name) => FILE_WHITELIST.contains(&file_name.as_str()),
+            Err(_) => false,
+        })
+        .map(|path| path.path())
+        .collect::<Vec<PathBuf>>();
+    files.sort();
+    Ok(files)
+}
+
+pub fn default_sdk_path() -> &'static str {
+    option_env!("SDK_PATH").unwrap_or("./JSON-SDK")
+}
+
+#[derive(Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct SdkAttribute {
     pub Name: String,
     pub Type: String,
-    #[serde(deserialize_with = "deserialize_hex")]
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
     pub Size: u32,
-    #[serde(deserialize_with = "deserialize_hex")]
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
     pub Offset: u32,
+    /// Mask of the bit inside the byte at `Offset`, for bitfield bools.
+    #[serde(
+        default,
+        alias = "FieldMask",
+        serialize_with = "serialize_optional_hex",
+        deserialize_with = "deserialize_optional_hex"
+    )]
+    pub BitMask: Option<u8>,
+    /// Set when the dump declares the member as a bitfield, either as a flag
+    /// or by giving its size in bits.
+    #[serde(
+        default,
+        alias = "IsBitField",
+        alias = "BitSize",
+        deserialize_with = "deserialize_bit_field"
+    )]
+    pub BitField: bool,
 }
 
-#[derive(Deserialize)]
+impl SdkAttribute {
+    /// Value of a `bool` member from the byte at its offset.
+    pub fn bool_from_byte(&self, byte: u8) -> bool {
+        match self.BitMask {
+            Some(mask) => byte & mask != 0,
+            None => byte != 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct SdkClass {
     pub Super: String,
-    #[serde(deserialize_with = "deserialize_hex")]
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
     pub FullSize: u32,
-    #[serde(deserialize_with = "deserialize_hex")]
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
     pub InheritedSize: u32,
-    #[serde(deserialize_with = "deserialize_hex")]
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
     pub ClassSize: u32,
     pub Attributes: Vec<SdkAttribute>,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct SdkStruct {
-    #[serde(deserialize_with = "deserialize_hex")]
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
     pub ClassSize: u32,
     pub Attributes: Vec<SdkAttribute>,
 }
 
+#[derive(Serialize, Deserialize)]
+struct SdkCache {
+    version: u32,
+    hash: u64,
+    classes: HashMap<String, SdkClass>,
+    structs: HashMap<String, SdkStruct>,
+    metadata: HashMap<String, String>,
+}
+
 pub struct SdkService {
     pub classes: HashMap<String, SdkClass>,
     pub structs: HashMap<String, SdkStruct>,
+    /// Non-class keys found in the dumps (author, game version...).
+    pub metadata: HashMap<String, String>,
 }
 impl SdkService {
     pub fn new() -> Self {
         Self {
             classes: HashMap::new(),
             structs: HashMap::new(),
+            metadata: HashMap::new(),
         }
     }
 
+    pub fn from_dir<P: AsRef<Path>>(sdk_path: P) -> Self {
+        let mut sdk_service = Self::new();
+        sdk_service.scan_sdk_dir(sdk_path);
+        sdk_service
+    }
+
+    /// Loads the default SDK directory with the overrides file layered over it.
     pub fn scan_sdk(&mut self) {
-        let sdk_path = option_env!("SDK_PATH").unwrap_or("./JSON-SDK");
-        let paths = fs::read_dir(sdk_path)
-            .unwrap()
-            .filter_map(|path| match path {
-                Ok(p) => Some(p),
-                Err(_) => None,
-            });
+        self.scan_sdk_dir(default_sdk_path());
+        if let Err(v) = self.apply_overrides(default_overrides_path()) {
+            panic!("{}", v);
+        }
+    }
+
+    pub fn apply_overrides<P: AsRef<Path>>(&mut self, overrides_path: P) -> Result<(), String> {
+        let overrides = SdkOverrides::from_file(overrides_path)?;
+        for line in overrides.apply(self) {
+            eprintln!("{}", line);
+        }
+        Ok(())
+    }
+
+    pub fn try_from_dir<P: AsRef<Path>>(sdk_path: P) -> Result<Self, String> {
+        let mut sdk_service = Self::new();
+        sdk_service.try_scan_sdk_dir(sdk_path, true)?;
+        Ok(sdk_service)
+    }
+
+    /// Like `try_from_dir`, without reading or writing `sdk.cache`. For
+    /// directories that are only looked at once, such as an old dump.
+    pub fn try_from_dir_uncached<P: AsRef<Path>>(sdk_path: P) -> Result<Self, String> {
+        let mut sdk_service = Self::new();
+        sdk_service.try_scan_sdk_dir(sdk_path, false)?;
+        Ok(sdk_service)
+    }
+
+    pub fn scan_sdk_dir<P: AsRef<Path>>(&mut self, sdk_path: P) {
+        if let Err(v) = self.try_scan_sdk_dir(sdk_path, true) {
+            panic!("{}", v);
+        }
+    }
 
+    fn try_scan_sdk_dir<P: AsRef<Path>>(
+        &mut self,
+        sdk_path: P,
+        use_cache: bool,
+    ) -> Result<(), String> {
+        let sdk_path = sdk_path.as_ref();
+        let paths = sdk_dir_files(sdk_path)?;
+
+        let mut hasher = StableHasher::new();
+        for path in &paths {
+            if let Err(v) = hash_file_metadata(&mut hasher, path) {
+                return Err(format!("File: {} \n{}", path.display(), v));
+            }
+        }
+        let hash = hasher.finish();
+
+        let cache_path = sdk_path.join(SDK_CACHE_FILE);
+        let cache = match use_cache {
+            true => Self::load_cache(&cache_path, hash),
+            false => None,
+        };
+        if let Some(cache) = cache {
+            self.classes.extend(cache.classes);
+            self.structs.extend(cache.structs);
+            self.metadata.extend(cache.metadata);
+            return Ok(());
+        }
+
+        let mut files = vec![];
         for path in paths {
-            let file_name = path.file_name().into_string().unwrap();
-            if !FILE_WHITELIST.contains(&file_name.as_str()) {
-                continue;
+            match fs::read(&path) {
+                Ok(content) => files.push((path, content)),
+                Err(v) => return Err(format!("File: {} \n{}", path.display(), v)),
             }
+        }
 
-            let path_buff = path.path();
+        for (path_buff, content) in files {
             let string_path = path_buff.to_str().unwrap();
 
-            let file = fs::File::open(path.path()).unwrap();
             if string_path.ends_with("Classes.json") {
-                match serde_json::from_reader::<_, HashMap<String, SdkClass>>(file) {
-                    Ok(v) => self.classes.extend(v),
-                    Err(v) => panic!("File: {} \n{}", string_path, v),
+                match parse_sdk_file::<SdkClass>(&content) {
+                    Ok((classes, metadata)) => {
+                        self.classes.extend(classes);
+                        self.metadata.extend(metadata);
+                    }
+                    Err(v) => return Err(format!("File: {} \n{}", string_path, v)),
                 }
             } else if string_path.ends_with("Structs.json") {
-                match serde_json::from_reader::<_, HashMap<String, SdkStruct>>(file) {
-                    Ok(v) => self.structs.extend(v),
-                    Err(v) => panic!("File: {} \n{}", string_path, v),
+                match parse_sdk_file::<SdkStruct>(&content) {
+                    Ok((structs, metadata)) => {
+                        self.structs.extend(structs);
+                        self.metadata.extend(metadata);
+                    }
+                    Err(v) => return Err(format!("File: {} \n{}", string_path, v)),
                 }
             } else {
-                println!("Could not scan {}", string_path);
+                eprintln!("Could not scan {}", string_path);
                 continue;
             }
         }
+
+        for class in self.classes.values_mut() {
+            infer_bit_masks(&mut class.Attributes);
+        }
+        for struct_ in self.structs.values_mut() {
+            infer_bit_masks(&mut struct_.Attributes);
+        }
+
+        if use_cache {
+            self.save_cache(&cache_path, hash);
+        }
+        Ok(())
+    }
+
+    /// Returns the cached SDK if it was built from JSON files matching `hash`,
+    /// which covers their names, sizes and modification times.
+    fn load_cache(cache_path: &Path, hash: u64) -> Option<SdkCache> {
+        let bytes = fs::read(cache_path).ok()?;
+        let cache = bincode::deserialize::<SdkCache>(&bytes).ok()?;
+        if cache.version != SDK_CACHE_VERSION || cache.hash != hash {
+            return None;
+        }
+        Some(cache)
+    }
+
+    fn save_cache(&mut self, cache_path: &Path, hash: u64) {
+        let cache = SdkCache {
+            version: SDK_CACHE_VERSION,
+            hash,
+            classes: std::mem::take(&mut self.classes),
+            structs: std::mem::take(&mut self.structs),
+            metadata: std::mem::take(&mut self.metadata),
+        };
+        match bincode::serialize(&cache) {
+            Ok(bytes) => {
+                if let Err(v) = fs::write(cache_path, bytes) {
+                    eprintln!("Could not write SDK cache {}: {}", cache_path.display(), v);
+                }
+            }
+            Err(v) => eprintln!("Could not serialize SDK cache: {}", v),
+        }
+        self.classes = cache.classes;
+        self.structs = cache.structs;
+        self.metadata = cache.metadata;
+    }
+
+    /// Removes the metadata keys from the SDK files of `sdk_path`, in place.
+    /// Returns the removed keys along with the file they were found in. Every
+    /// file is parsed before any is written, so a malformed one leaves the
+    /// directory untouched.
+    pub fn normalize_dir<P: AsRef<Path>>(sdk_path: P) -> io::Result<Vec<(PathBuf, String)>> {
+        let files = sdk_dir_files(sdk_path.as_ref()).map_err(io::Error::other)?;
+
+        let mut normalized = vec![];
+        for path in files {
+            let content = fs::read(&path)?;
+            let raw = serde_json::from_slice::<Map<String, Value>>(&content)?;
+            normalized.push((path, raw));
+        }
+
+        let mut removed = vec![];
+        for (path, mut raw) in normalized {
+            let metadata_keys = raw
+                .iter()
+                .filter(|(_, value)| !is_sdk_entry(value))
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<String>>();
+            if metadata_keys.is_empty() {
+                continue;
+            }
+
+            for key in metadata_keys {
+                raw.remove(&key);
+                removed.push((path.clone(), key));
+            }
+            fs::write(&path, serde_json::to_string_pretty(&raw)?)?;
+        }
+        Ok(removed)
     }
 
     /// ## Example:
@@ -142,6 +536,18 @@ impl SdkService {
         }
     }
 
+    /// Like `get_offset`, for members that only exist in some SDK dumps.
+    pub fn try_get_offset(&self, attribute_path: &str) -> Option<u32> {
+        self.get_attribute(attribute_path).map(|v| v.Offset)
+    }
+
+    pub fn get_attribute(&self, attribute_path: &str) -> Option<&SdkAttribute> {
+        let (struct_or_class_name, attribute_name) = attribute_path.split_once('.')?;
+        self.get_attributes(struct_or_class_name)?
+            .iter()
+            .find(|v| v.Name == attribute_name)
+    }
+
     pub fn get_attribute_size(&self, attribute_path: &'static str) -> u32 {
         let split = attribute_path.split('.').collect::<Vec<&str>>();
         let (struct_or_class_name, attribute_name) = match split.as_slice() {
@@ -184,6 +590,43 @@ impl SdkService {
         }
     }
 
+    pub fn try_get_class_or_struct_size(&self, struct_or_class_name: &str) -> Option<u32> {
+        match self.classes.get(struct_or_class_name) {
+            Some(class) => Some(class.ClassSize),
+            None => self.structs.get(struct_or_class_name).map(|v| v.ClassSize),
+        }
+    }
+
+    /// Size of a class including its parents, or of a struct.
+    pub fn get_full_size(&self, struct_or_class_name: &str) -> u32 {
+        if let Some(class) = self.classes.get(struct_or_class_name) {
+            class.FullSize
+        } else if let Some(struct_) = self.structs.get(struct_or_class_name) {
+            struct_.ClassSize
+        } else {
+            panic!(
+                "{}",
+                format!(
+                    "Class or Struct \"{}\" does not exist",
+                    struct_or_class_name
+                )
+            );
+        }
+    }
+
+    pub(crate) fn get_attributes_mut(
+        &mut self,
+        struct_or_class_name: &str,
+    ) -> Option<&mut Vec<SdkAttribute>> {
+        if let Some(class) = self.classes.get_mut(struct_or_class_name) {
+            Some(&mut class.Attributes)
+        } else if let Some(struct_) = self.structs.get_mut(struct_or_class_name) {
+            Some(&mut struct_.Attributes)
+        } else {
+            None
+        }
+    }
+
     fn get_attributes(&self, struct_or_class_name: &str) -> Option<&Vec<SdkAttribute>> {
         if let Some(class) = self.classes.get(struct_or_class_name) {
             Some(&class.Attributes)
@@ -193,13 +636,298 @@ impl SdkService {
             None
         }
     }
+
+    /// SDK name of a C++ type as written in the `Type` of attributes:
+    /// `class AActor*` and `FVector` become `Actor` and `Vector`.
+    pub fn resolve_type_name<'a>(&self, type_name: &'a str) -> Option<&'a str> {
+        let name = type_name
+            .trim()
+            .trim_start_matches("class ")
+            .trim_start_matches("struct ")
+            .trim_end_matches('*')
+            .trim();
+        if self.get_attributes(name).is_some() {
+            return Some(name);
+        }
+        let unprefixed = name.strip_prefix(['U', 'A', 'F'])?;
+        self.get_attributes(unprefixed).map(|_| unprefixed)
+    }
+
+    /// Name of the parent class, `None` for structs and root classes.
+    pub fn get_super(&self, class_name: &str) -> Option<&str> {
+        let class = self.classes.get(class_name)?;
+        if class.Super.is_empty() || class.Super == "None" {
+            return None;
+        }
+        Some(&class.Super)
+    }
+
+    /// Direct children of `class_name`, sorted by name.
+    pub fn get_subclasses(&self, class_name: &str) -> Vec<&str> {
+        let mut subclasses = self
+            .classes
+            .iter()
+            .filter(|(_, class)| class.Super == class_name)
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<&str>>();
+        subclasses.sort();
+        subclasses
+    }
+
+    /// Attributes of a class or struct including the inherited ones, paired with
+    /// the name of the class declaring them. Root class attributes come first.
+    pub fn get_flattened_attributes<'a>(
+        &'a self,
+        struct_or_class_name: &'a str,
+    ) -> Option<Vec<(&'a str, &'a SdkAttribute)>> {
+        let mut chain = vec![struct_or_class_name];
+        let mut current = struct_or_class_name;
+        while let Some(super_name) = self.get_super(current) {
+            if self.get_attributes(super_name).is_none() || chain.contains(&super_name) {
+                break;
+            }
+            chain.push(super_name);
+            current = super_name;
+        }
+
+        let mut flattened = vec![];
+        for name in chain.into_iter().rev() {
+            for attribute in self.get_attributes(name)? {
+                flattened.push((name, attribute));
+            }
+        }
+        Some(flattened)
+    }
+}
+
+/// Source of unique SDK generations, shared by every `SdkHandle` so that
+/// caches keyed by generation never mix two SDKs up.
+static NEXT_SDK_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+struct LoadedSdk {
+    generation: u64,
+    sdk: Arc<SdkService>,
+}
+
+impl LoadedSdk {
+    fn new(sdk: SdkService) -> Self {
+        Self {
+            generation: NEXT_SDK_GENERATION.fetch_add(1, Ordering::AcqRel),
+            sdk: Arc::new(sdk),
+        }
+    }
 }
 
-pub fn sdk_service() -> &'static SdkService {
-    static SDK_SERVICE: OnceLock<SdkService> = OnceLock::new();
-    SDK_SERVICE.get_or_init(|| {
+/// An SDK that can be swapped for a newer one while it is in use. Clones
+/// share the same SDK and see each other's reloads.
+#[derive(Clone)]
+pub struct SdkHandle {
+    loaded: Arc<ArcSwap<LoadedSdk>>,
+}
+
+impl SdkHandle {
+    pub fn new(sdk_service: SdkService) -> Self {
+        Self {
+            loaded: Arc::new(ArcSwap::from_pointee(LoadedSdk::new(sdk_service))),
+        }
+    }
+
+    /// Loads the default SDK directory with the overrides file layered over it.
+    pub fn load_default() -> Self {
         let mut sdk_service = SdkService::new();
         sdk_service.scan_sdk();
-        sdk_service
-    })
+        Self::new(sdk_service)
+    }
+
+    pub fn get(&self) -> Arc<SdkService> {
+        self.loaded.load().sdk.clone()
+    }
+
+    /// Changes every time the SDK is reloaded. Anything caching offsets
+    /// should re-resolve them when this changes.
+    pub fn generation(&self) -> u64 {
+        self.loaded.load().generation
+    }
+
+    /// Parses the SDK directory again and swaps it in. The current SDK is kept
+    /// if the new one cannot be parsed (e.g. a file is still being written).
+    pub fn reload(&self) -> Result<(), String> {
+        let mut sdk_service = SdkService::try_from_dir(default_sdk_path())?;
+        sdk_service.apply_overrides(default_overrides_path())?;
+        self.loaded.store(Arc::new(LoadedSdk::new(sdk_service)));
+        Ok(())
+    }
+
+    /// Polls the SDK directory and the overrides file every `interval` and
+    /// reloads the SDK when one of them changes.
+    pub fn watch(&self, interval: Duration) -> JoinHandle<()> {
+        let handle = self.clone();
+        thread::spawn(move || {
+            let sdk_path = Path::new(default_sdk_path());
+            let overrides_path = Path::new(default_overrides_path());
+            let mut fingerprint = sdk_dir_fingerprint(sdk_path, overrides_path);
+            loop {
+                thread::sleep(interval);
+                let new_fingerprint = sdk_dir_fingerprint(sdk_path, overrides_path);
+                if new_fingerprint.is_none() || new_fingerprint == fingerprint {
+                    continue;
+                }
+                fingerprint = new_fingerprint;
+
+                match handle.reload() {
+                    Ok(()) => eprintln!("SDK reloaded"),
+                    Err(v) => eprintln!("Could not reload SDK: {}", v),
+                }
+            }
+        })
+    }
+}
+
+/// A value derived from the SDK, such as a set of offsets, that is resolved
+/// again the first time it is used after the SDK is reloaded.
+pub struct SdkResolved<T> {
+    generation: u64,
+    value: T,
+    resolve: fn(&SdkService) -> T,
+}
+
+impl<T> SdkResolved<T> {
+    pub fn new(sdk_handle: &SdkHandle, resolve: fn(&SdkService) -> T) -> Self {
+        let loaded = sdk_handle.loaded.load();
+        Self {
+            generation: loaded.generation,
+            value: resolve(&loaded.sdk),
+            resolve,
+        }
+    }
+
+    pub fn get(&mut self, sdk_handle: &SdkHandle) -> &T {
+        let loaded = sdk_handle.loaded.load();
+        if loaded.generation != self.generation {
+            self.generation = loaded.generation;
+            self.value = (self.resolve)(&loaded.sdk);
+        }
+        &self.value
+    }
+}
+
+fn sdk_dir_fingerprint(sdk_path: &Path, overrides_path: &Path) -> Option<u64> {
+    let mut hasher = StableHasher::new();
+    for path in sdk_dir_files(sdk_path).ok()? {
+        hash_file_metadata(&mut hasher, &path).ok()?;
+    }
+    if overrides_path.exists() {
+        hash_file_metadata(&mut hasher, overrides_path).ok()?;
+    }
+    Some(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(name: &str, type_name: &str, offset: u32, bit_mask: Option<u8>) -> SdkAttribute {
+        SdkAttribute {
+            Name: name.to_string(),
+            Type: type_name.to_string(),
+            Size: 1,
+            Offset: offset,
+            BitMask: bit_mask,
+            BitField: false,
+        }
+    }
+
+    fn masks(attributes: &[SdkAttribute]) -> Vec<Option<u8>> {
+        attributes.iter().map(|v| v.BitMask).collect()
+    }
+
+    #[test]
+    fn bools_sharing_a_byte_get_one_bit_each() {
+        let mut attributes = vec![
+            attribute("bHidden", "bool", 0x7C, None),
+            attribute("bNetTemporary", "bool", 0x7C, None),
+            attribute("bNetStartup", "bool", 0x7C, None),
+            attribute("bReplicates", "bool", 0x7D, None),
+        ];
+        infer_bit_masks(&mut attributes);
+        assert_eq!(
+            masks(&attributes),
+            vec![Some(0b001), Some(0b010), Some(0b100), None]
+        );
+    }
+
+    #[test]
+    fn masks_from_the_dump_are_kept() {
+        let mut attributes = vec![
+            attribute("bHidden", "bool", 0x7C, Some(0x10)),
+            attribute("bNetTemporary", "bool", 0x7C, None),
+        ];
+        infer_bit_masks(&mut attributes);
+        assert_eq!(masks(&attributes), vec![Some(0x10), Some(0x01)]);
+    }
+
+    #[test]
+    fn bools_marked_as_bitfields_get_a_free_bit() {
+        let mut attributes = vec![
+            attribute("bHidden", "bool", 0x7C, None),
+            attribute("bNetTemporary", "bool", 0x7D, Some(0x01)),
+            attribute("bCanBeDamaged", "bool", 0x7D, None),
+            attribute("bReplicates", "bool", 0x7E, None),
+        ];
+        attributes[0].BitField = true;
+        infer_bit_masks(&mut attributes);
+        assert_eq!(
+            masks(&attributes),
+            vec![Some(0x01), Some(0x01), Some(0x02), None]
+        );
+
+        let json = r#"{"Name": "bHidden", "Type": "bool", "Size": "0x1", "Offset": "0x7C", "BitSize": "0x1"}"#;
+        assert!(serde_json::from_str::<SdkAttribute>(json).unwrap().BitField);
+    }
+
+    #[test]
+    fn other_members_at_the_same_offset_are_not_bitfields() {
+        let mut attributes = vec![
+            attribute("Flags", "uint8_t", 0x7C, None),
+            attribute("bHidden", "bool", 0x7C, None),
+        ];
+        infer_bit_masks(&mut attributes);
+        assert_eq!(masks(&attributes), vec![None, None]);
+    }
+
+    #[test]
+    fn more_bools_than_bits_are_left_alone() {
+        let mut attributes = (0..9)
+            .map(|i| attribute(&format!("b{}", i), "bool", 0x7C, None))
+            .collect::<Vec<SdkAttribute>>();
+        infer_bit_masks(&mut attributes);
+        assert!(masks(&attributes).iter().all(|v| v.is_none()));
+    }
+
+    #[test]
+    fn the_cache_stores_plain_integers() {
+        let json = r#"{"Name": "bHidden", "Type": "bool", "Size": "0x1", "Offset": "0x7C", "BitMask": "0x10"}"#;
+        let attribute = serde_json::from_str::<SdkAttribute>(json).unwrap();
+
+        let bytes = bincode::serialize(&attribute).unwrap();
+        assert!(bytes.windows(4).any(|v| v == 0x7Cu32.to_le_bytes()));
+        assert!(!bytes.windows(2).any(|v| v == b"0x"));
+
+        let cached = bincode::deserialize::<SdkAttribute>(&bytes).unwrap();
+        assert_eq!(
+            (cached.Size, cached.Offset, cached.BitMask),
+            (1, 0x7C, Some(0x10))
+        );
+        assert_eq!(
+            serde_json::to_value(&cached).unwrap()["Offset"],
+            Value::from("0x7C")
+        );
+    }
+
+    #[test]
+    fn stable_hasher_matches_fnv1a() {
+        let mut hasher = StableHasher::new();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xAF63_DC4C_8601_EC8C);
+    }
 }
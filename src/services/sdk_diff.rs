@@ -0,0 +1,226 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::services::sdk::{SdkAttribute, SdkService};
+
+#[derive(Serialize)]
+pub struct SizeChange {
+    pub field: &'static str,
+    pub old: u32,
+    pub new: u32,
+}
+
+#[derive(Serialize)]
+pub struct MemberChange {
+    pub name: String,
+    pub old_offset: u32,
+    pub new_offset: u32,
+    pub old_type: String,
+    pub new_type: String,
+    pub old_size: u32,
+    pub new_size: u32,
+}
+
+#[derive(Serialize)]
+pub struct TypeDiff {
+    pub name: String,
+    pub size_changes: Vec<SizeChange>,
+    pub added_members: Vec<String>,
+    pub removed_members: Vec<String>,
+    pub member_changes: Vec<MemberChange>,
+}
+
+impl TypeDiff {
+    fn is_empty(&self) -> bool {
+        self.size_changes.is_empty()
+            && self.added_members.is_empty()
+            && self.removed_members.is_empty()
+            && self.member_changes.is_empty()
+    }
+}
+
+#[derive(Serialize)]
+pub struct SdkDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<TypeDiff>,
+}
+
+impl SdkDiff {
+    /// Compares every class and struct of `old` against `new`.
+    pub fn new(old: &SdkService, new: &SdkService) -> Self {
+        let mut diff = Self {
+            added: vec![],
+            removed: vec![],
+            changed: vec![],
+        };
+
+        let class_names: BTreeSet<&String> = old.classes.keys().chain(new.classes.keys()).collect();
+        for name in class_names {
+            match (old.classes.get(name), new.classes.get(name)) {
+                (Some(old_class), Some(new_class)) => {
+                    let mut size_changes = vec![];
+                    push_size_change(
+                        &mut size_changes,
+                        "ClassSize",
+                        old_class.ClassSize,
+                        new_class.ClassSize,
+                    );
+                    push_size_change(
+                        &mut size_changes,
+                        "FullSize",
+                        old_class.FullSize,
+                        new_class.FullSize,
+                    );
+                    diff.push_changed(
+                        name,
+                        size_changes,
+                        &old_class.Attributes,
+                        &new_class.Attributes,
+                    );
+                }
+                (None, Some(_)) => diff.added.push(name.clone()),
+                (Some(_), None) => diff.removed.push(name.clone()),
+                (None, None) => unreachable!(),
+            }
+        }
+
+        let struct_names: BTreeSet<&String> =
+            old.structs.keys().chain(new.structs.keys()).collect();
+        for name in struct_names {
+            match (old.structs.get(name), new.structs.get(name)) {
+                (Some(old_struct), Some(new_struct)) => {
+                    let mut size_changes = vec![];
+                    push_size_change(
+                        &mut size_changes,
+                        "ClassSize",
+                        old_struct.ClassSize,
+                        new_struct.ClassSize,
+                    );
+                    diff.push_changed(
+                        name,
+                        size_changes,
+                        &old_struct.Attributes,
+                        &new_struct.Attributes,
+                    );
+                }
+                (None, Some(_)) => diff.added.push(name.clone()),
+                (Some(_), None) => diff.removed.push(name.clone()),
+                (None, None) => unreachable!(),
+            }
+        }
+
+        diff
+    }
+
+    fn push_changed(
+        &mut self,
+        name: &str,
+        size_changes: Vec<SizeChange>,
+        old_attributes: &[SdkAttribute],
+        new_attributes: &[SdkAttribute],
+    ) {
+        let mut type_diff = TypeDiff {
+            name: name.to_string(),
+            size_changes,
+            added_members: vec![],
+            removed_members: vec![],
+            member_changes: vec![],
+        };
+
+        for old_attribute in old_attributes {
+            match new_attributes.iter().find(|v| v.Name == old_attribute.Name) {
+                Some(new_attribute) => {
+                    if old_attribute.Offset != new_attribute.Offset
+                        || old_attribute.Type != new_attribute.Type
+                        || old_attribute.Size != new_attribute.Size
+                    {
+                        type_diff.member_changes.push(MemberChange {
+                            name: old_attribute.Name.clone(),
+                            old_offset: old_attribute.Offset,
+                            new_offset: new_attribute.Offset,
+                            old_type: old_attribute.Type.clone(),
+                            new_type: new_attribute.Type.clone(),
+                            old_size: old_attribute.Size,
+                            new_size: new_attribute.Size,
+                        });
+                    }
+                }
+                None => type_diff.removed_members.push(old_attribute.Name.clone()),
+            }
+        }
+        for new_attribute in new_attributes {
+            if !old_attributes.iter().any(|v| v.Name == new_attribute.Name) {
+                type_diff.added_members.push(new_attribute.Name.clone());
+            }
+        }
+
+        if !type_diff.is_empty() {
+            self.changed.push(type_diff);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn push_size_change(size_changes: &mut Vec<SizeChange>, field: &'static str, old: u32, new: u32) {
+    if old != new {
+        size_changes.push(SizeChange { field, old, new });
+    }
+}
+
+impl fmt::Display for SdkDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No differences");
+        }
+        for name in &self.added {
+            writeln!(f, "+ {}", name)?;
+        }
+        for name in &self.removed {
+            writeln!(f, "- {}", name)?;
+        }
+        for type_diff in &self.changed {
+            writeln!(f, "~ {}", type_diff.name)?;
+            for size_change in &type_diff.size_changes {
+                writeln!(
+                    f,
+                    "    {}: 0x{:X} -> 0x{:X}",
+                    size_change.field, size_change.old, size_change.new
+                )?;
+            }
+            for member in &type_diff.added_members {
+                writeln!(f, "    + {}", member)?;
+            }
+            for member in &type_diff.removed_members {
+                writeln!(f, "    - {}", member)?;
+            }
+            for change in &type_diff.member_changes {
+                write!(f, "    ~ {}", change.name)?;
+                if change.old_offset != change.new_offset {
+                    write!(
+                        f,
+                        " offset 0x{:X} -> 0x{:X}",
+                        change.old_offset, change.new_offset
+                    )?;
+                }
+                if change.old_size != change.new_size {
+                    write!(
+                        f,
+                        " size 0x{:X} -> 0x{:X}",
+                        change.old_size, change.new_size
+                    )?;
+                }
+                if change.old_type != change.new_type {
+                    write!(f, " type {} -> {}", change.old_type, change.new_type)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,484 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::services::sdk::SdkService;
+
+/// SDK type, Rust type, C type and size of the primitives we know how to map.
+/// Everything else is exported as pointers or opaque byte arrays.
+const PRIMITIVES: [(&str, &str, &str, u32); 20] = [
+    ("bool", "u8", "bool", 1),
+    ("char", "i8", "int8_t", 1),
+    ("int8_t", "i8", "int8_t", 1),
+    ("byte", "u8", "uint8_t", 1),
+    ("unsigned char", "u8", "uint8_t", 1),
+    ("uint8_t", "u8", "uint8_t", 1),
+    ("short", "i16", "int16_t", 2),
+    ("int16_t", "i16", "int16_t", 2),
+    ("uint16_t", "u16", "uint16_t", 2),
+    ("int", "i32", "int32_t", 4),
+    ("int32_t", "i32", "int32_t", 4),
+    ("unsigned int", "u32", "uint32_t", 4),
+    ("uint32_t", "u32", "uint32_t", 4),
+    ("long long", "i64", "int64_t", 8),
+    ("int64_t", "i64", "int64_t", 8),
+    ("unsigned long long", "u64", "uint64_t", 8),
+    ("uint64_t", "u64", "uint64_t", 8),
+    ("float", "f32", "float", 4),
+    ("double", "f64", "double", 8),
+    ("wchar_t", "u16", "char16_t", 2),
+];
+
+/// Rust type and size of a primitive SDK type.
+pub(crate) fn primitive_type(sdk_type: &str) -> Option<(&'static str, u32)> {
+    PRIMITIVES
+        .iter()
+        .find(|(name, _, _, _)| *name == sdk_type)
+        .map(|(_, rust_type, _, size)| (*rust_type, *size))
+}
+
+/// Strict and reserved Rust keywords, escaped as raw identifiers.
+const RUST_KEYWORDS: [&str; 52] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "Self", "self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Rust keywords that cannot be raw identifiers.
+const RUST_NON_RAW_KEYWORDS: [&str; 5] = ["_", "crate", "self", "Self", "super"];
+
+const CPP_KEYWORDS: [&str; 96] = [
+    "alignas",
+    "alignof",
+    "and",
+    "and_eq",
+    "asm",
+    "auto",
+    "bitand",
+    "bitor",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "char8_t",
+    "char16_t",
+    "char32_t",
+    "class",
+    "compl",
+    "concept",
+    "const",
+    "consteval",
+    "constexpr",
+    "constinit",
+    "const_cast",
+    "continue",
+    "co_await",
+    "co_return",
+    "co_yield",
+    "decltype",
+    "default",
+    "delete",
+    "do",
+    "double",
+    "dynamic_cast",
+    "else",
+    "enum",
+    "explicit",
+    "export",
+    "extern",
+    "false",
+    "final",
+    "float",
+    "for",
+    "friend",
+    "goto",
+    "if",
+    "import",
+    "inline",
+    "int",
+    "long",
+    "module",
+    "mutable",
+    "namespace",
+    "new",
+    "noexcept",
+    "not",
+    "not_eq",
+    "nullptr",
+    "operator",
+    "or",
+    "or_eq",
+    "override",
+    "private",
+    "protected",
+    "public",
+    "register",
+    "reinterpret_cast",
+    "requires",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "static_assert",
+    "static_cast",
+    "struct",
+    "switch",
+    "template",
+    "this",
+    "thread_local",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "typeid",
+    "typename",
+    "union",
+    "unsigned",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "wchar_t",
+    "while",
+    "xor",
+    "xor_eq",
+];
+
+fn rust_identifier(name: &str) -> String {
+    if RUST_NON_RAW_KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else if RUST_KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn cpp_identifier(name: &str) -> String {
+    if CPP_KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
+
+enum FieldKind {
+    Primitive(&'static str, &'static str),
+    Pointer,
+    Bytes,
+    Padding,
+    /// Bitfields and unions sharing the offset of a previous field.
+    Overlap,
+}
+
+struct Field {
+    name: String,
+    offset: u32,
+    size: u32,
+    sdk_type: String,
+    kind: FieldKind,
+}
+
+struct Layout {
+    name: String,
+    size: u32,
+    fields: Vec<Field>,
+}
+
+/// `name` made of ASCII letters, digits and underscores, then passed through
+/// the keyword escaping of the target language.
+fn identifier(name: &str, escape: fn(&str) -> String) -> String {
+    let mut identifier = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    escape(&identifier)
+}
+
+fn layout(sdk: &SdkService, name: &str, escape: fn(&str) -> String) -> Result<Layout, String> {
+    let mut attributes = sdk
+        .get_flattened_attributes(name)
+        .ok_or(format!("Class or Struct \"{}\" does not exist", name))?;
+    attributes.sort_by_key(|(_, v)| v.Offset);
+    let size = sdk.get_full_size(name);
+
+    let mut fields = vec![];
+    let mut used_names = HashSet::new();
+    let mut cursor = 0;
+    for (_, attribute) in attributes {
+        let mut field_name = identifier(&attribute.Name, escape);
+        while !used_names.insert(field_name.clone()) {
+            field_name.push('_');
+        }
+        let sdk_type = match attribute.BitMask {
+            Some(v) => format!("{} (bit mask 0x{:02X})", attribute.Type, v),
+            None => attribute.Type.clone(),
+        };
+
+        if attribute.Size == 0 || attribute.Offset < cursor {
+            fields.push(Field {
+                name: field_name,
+                offset: attribute.Offset,
+                size: attribute.Size,
+                sdk_type,
+                kind: FieldKind::Overlap,
+            });
+            continue;
+        }
+        if attribute.Offset + attribute.Size > size {
+            return Err(format!(
+                "{}.{} at 0x{:X} (size 0x{:X}) is outside of {} (size 0x{:X})",
+                name, attribute.Name, attribute.Offset, attribute.Size, name, size
+            ));
+        }
+        if attribute.Offset > cursor {
+            fields.push(Field {
+                name: format!("pad_0x{:04X}", cursor),
+                offset: cursor,
+                size: attribute.Offset - cursor,
+                sdk_type: String::new(),
+                kind: FieldKind::Padding,
+            });
+        }
+
+        let kind = match PRIMITIVES
+            .iter()
+            .find(|(sdk_type, _, _, size)| *sdk_type == attribute.Type && *size == attribute.Size)
+        {
+            // The byte holding the bitfield, not a bool of its own.
+            Some(_) if attribute.BitMask.is_some() => FieldKind::Primitive("u8", "uint8_t"),
+            Some((_, rust_type, c_type, _)) => FieldKind::Primitive(rust_type, c_type),
+            None if attribute.Type.ends_with('*') && attribute.Size == 8 => FieldKind::Pointer,
+            None => FieldKind::Bytes,
+        };
+        fields.push(Field {
+            name: field_name,
+            offset: attribute.Offset,
+            size: attribute.Size,
+            sdk_type,
+            kind,
+        });
+        cursor = attribute.Offset + attribute.Size;
+    }
+    if cursor < size {
+        fields.push(Field {
+            name: format!("pad_0x{:04X}", cursor),
+            offset: cursor,
+            size: size - cursor,
+            sdk_type: String::new(),
+            kind: FieldKind::Padding,
+        });
+    }
+
+    Ok(Layout {
+        name: identifier(name, escape),
+        size,
+        fields,
+    })
+}
+
+/// ## Example:
+/// ```
+/// export_rust(sdk_service, &["Crew"]) // #[repr(C, packed)] pub struct Crew { ... }
+/// ```
+pub fn export_rust(sdk: &SdkService, names: &[&str]) -> Result<String, String> {
+    let mut output = String::from("// Generated from the JSON-SDK, do not edit.\n\n");
+    output.push_str("use std::mem::{offset_of, size_of};\n");
+
+    for name in names {
+        let layout = layout(sdk, name, rust_identifier)?;
+
+        writeln!(output).unwrap();
+        writeln!(output, "#[repr(C, packed)]").unwrap();
+        writeln!(output, "#[allow(non_snake_case)]").unwrap();
+        writeln!(output, "pub struct {} {{", layout.name).unwrap();
+        for field in &layout.fields {
+            let rust_type = match field.kind {
+                FieldKind::Primitive(rust_type, _) => rust_type.to_string(),
+                FieldKind::Pointer => "*mut std::ffi::c_void".to_string(),
+                FieldKind::Bytes | FieldKind::Padding => format!("[u8; 0x{:X}]", field.size),
+                FieldKind::Overlap => {
+                    writeln!(
+                        output,
+                        "    // 0x{:04X} {} {} (overlaps previous field)",
+                        field.offset, field.sdk_type, field.name
+                    )
+                    .unwrap();
+                    continue;
+                }
+            };
+            match field.kind {
+                FieldKind::Padding => {
+                    writeln!(output, "    {}: {},", field.name, rust_type).unwrap()
+                }
+                _ => writeln!(
+                    output,
+                    "    pub {}: {}, // 0x{:04X} {}",
+                    field.name, rust_type, field.offset, field.sdk_type
+                )
+                .unwrap(),
+            }
+        }
+        writeln!(output, "}}").unwrap();
+
+        writeln!(
+            output,
+            "const _: () = assert!(size_of::<{}>() == 0x{:X});",
+            layout.name, layout.size
+        )
+        .unwrap();
+        for field in &layout.fields {
+            if let FieldKind::Padding | FieldKind::Overlap = field.kind {
+                continue;
+            }
+            writeln!(
+                output,
+                "const _: () = assert!(offset_of!({}, {}) == 0x{:X});",
+                layout.name, field.name, field.offset
+            )
+            .unwrap();
+        }
+    }
+    Ok(output)
+}
+
+/// ## Example:
+/// ```
+/// export_cpp(sdk_service, &["Crew"]) // struct Crew { ... }; static_assert(...);
+/// ```
+pub fn export_cpp(sdk: &SdkService, names: &[&str]) -> Result<String, String> {
+    let mut output = String::from("// Generated from the JSON-SDK, do not edit.\n");
+    output.push_str("#pragma once\n\n#include <cstddef>\n#include <cstdint>\n\n");
+    output.push_str("#pragma pack(push, 1)\n");
+
+    let mut asserts = String::new();
+    for name in names {
+        let layout = layout(sdk, name, cpp_identifier)?;
+
+        writeln!(output).unwrap();
+        writeln!(output, "struct {} {{", layout.name).unwrap();
+        for field in &layout.fields {
+            match field.kind {
+                FieldKind::Primitive(_, c_type) => writeln!(
+                    output,
+                    "    {} {}; // 0x{:04X} {}",
+                    c_type, field.name, field.offset, field.sdk_type
+                ),
+                FieldKind::Pointer => writeln!(
+                    output,
+                    "    void* {}; // 0x{:04X} {}",
+                    field.name, field.offset, field.sdk_type
+                ),
+                FieldKind::Bytes => writeln!(
+                    output,
+                    "    uint8_t {}[0x{:X}]; // 0x{:04X} {}",
+                    field.name, field.size, field.offset, field.sdk_type
+                ),
+                FieldKind::Padding => {
+                    writeln!(output, "    uint8_t {}[0x{:X}];", field.name, field.size)
+                }
+                FieldKind::Overlap => writeln!(
+                    output,
+                    "    // 0x{:04X} {} {} (overlaps previous field)",
+                    field.offset, field.sdk_type, field.name
+                ),
+            }
+            .unwrap();
+        }
+        writeln!(output, "}};").unwrap();
+
+        writeln!(
+            asserts,
+            "static_assert(sizeof({}) == 0x{:X}, \"{} size\");",
+            layout.name, layout.size, layout.name
+        )
+        .unwrap();
+        for field in &layout.fields {
+            if let FieldKind::Padding | FieldKind::Overlap = field.kind {
+                continue;
+            }
+            writeln!(
+                asserts,
+                "static_assert(offsetof({}, {}) == 0x{:X}, \"{}::{} offset\");",
+                layout.name, field.name, field.offset, layout.name, field.name
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(output, "\n#pragma pack(pop)\n").unwrap();
+    output.push_str(&asserts);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::sdk::{SdkAttribute, SdkStruct};
+
+    fn sdk(attributes: &[(&str, &str, u32)]) -> SdkService {
+        let mut sdk = SdkService::new();
+        sdk.structs.insert(
+            "Keywords".to_string(),
+            SdkStruct {
+                ClassSize: 4 * attributes.len() as u32,
+                Attributes: attributes
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (name, type_name, size))| SdkAttribute {
+                        Name: name.to_string(),
+                        Type: type_name.to_string(),
+                        Size: *size,
+                        Offset: 4 * index as u32,
+                        BitMask: None,
+                        BitField: false,
+                    })
+                    .collect(),
+            },
+        );
+        sdk
+    }
+
+    #[test]
+    fn rust_keywords_are_raw_or_renamed() {
+        let sdk = sdk(&[
+            ("type", "int", 4),
+            ("self", "int", 4),
+            ("Self", "int", 4),
+            ("loop", "float", 4),
+            ("class", "int", 4),
+        ]);
+        let output = export_rust(&sdk, &["Keywords"]).unwrap();
+
+        assert!(output.contains("    pub r#type: i32, // 0x0000 int\n"));
+        assert!(output.contains("    pub self_: i32, // 0x0004 int\n"));
+        assert!(output.contains("    pub Self_: i32, // 0x0008 int\n"));
+        assert!(output.contains("    pub r#loop: f32, // 0x000C float\n"));
+        assert!(output.contains("    pub class: i32, // 0x0010 int\n"));
+        assert!(output.contains("assert!(offset_of!(Keywords, r#loop) == 0xC);"));
+        assert!(output.contains("assert!(offset_of!(Keywords, self_) == 0x4);"));
+    }
+
+    #[test]
+    fn cpp_keywords_get_a_trailing_underscore() {
+        let sdk = sdk(&[
+            ("class", "int", 4),
+            ("class_", "int", 4),
+            ("default", "uint8_t", 1),
+            ("type", "int", 4),
+        ]);
+        let output = export_cpp(&sdk, &["Keywords"]).unwrap();
+
+        assert!(output.contains("    int32_t class_; // 0x0000 int\n"));
+        assert!(output.contains("    int32_t class__; // 0x0004 int\n"));
+        assert!(output.contains("    uint8_t default_; // 0x0008 uint8_t\n"));
+        assert!(output.contains("    int32_t type; // 0x000C int\n"));
+        assert!(output.contains("offsetof(Keywords, default_) == 0x8"));
+    }
+}
@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+
+use crate::services::sdk::{SdkAttribute, SdkClass, SdkService, SdkStruct};
+
+/// Offsets and sizes can be written either as integers or as "0x" strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OverrideValue {
+    Int(u32),
+    Hex(String),
+}
+
+impl OverrideValue {
+    fn into_u32<E: de::Error>(self) -> Result<u32, E> {
+        match self {
+            OverrideValue::Int(v) => Ok(v),
+            OverrideValue::Hex(v) => {
+                let digits = v.trim_start_matches("0x").trim_start_matches("0X");
+                u32::from_str_radix(digits, 16).map_err(de::Error::custom)
+            }
+        }
+    }
+}
+
+fn deserialize_value<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    OverrideValue::deserialize(deserializer)?.into_u32()
+}
+
+fn deserialize_optional_value<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<OverrideValue>::deserialize(deserializer)? {
+        Some(v) => Ok(Some(v.into_u32()?)),
+        None => Ok(None),
+    }
+}
+
+fn deserialize_optional_mask<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match deserialize_optional_value(deserializer)? {
+        Some(v) => u8::try_from(v).map(Some).map_err(de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// ## Example:
+/// ```toml
+/// [members."Crew.Players"]
+/// offset = "0x20"
+///
+/// [members."Actor.bHidden"]
+/// bit_mask = "0x2"
+///
+/// [classes.MissingStruct]
+/// size = "0x10"
+/// attributes = [{ name = "Value", type = "int", offset = "0x8", size = "0x4" }]
+/// ```
+#[derive(Deserialize, Default)]
+pub struct SdkOverrides {
+    #[serde(default)]
+    pub members: HashMap<String, MemberOverride>,
+    #[serde(default)]
+    pub classes: HashMap<String, ClassOverride>,
+}
+
+#[derive(Deserialize)]
+pub struct MemberOverride {
+    #[serde(default, deserialize_with = "deserialize_optional_value")]
+    pub offset: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_optional_value")]
+    pub size: Option<u32>,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_mask")]
+    pub bit_mask: Option<u8>,
+}
+
+/// Classes with a `super` are added as classes, the others as structs.
+#[derive(Deserialize)]
+pub struct ClassOverride {
+    #[serde(rename = "super")]
+    pub super_: Option<String>,
+    #[serde(deserialize_with = "deserialize_value")]
+    pub size: u32,
+    #[serde(default)]
+    pub attributes: Vec<AttributeOverride>,
+}
+
+#[derive(Deserialize)]
+pub struct AttributeOverride {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub type_: String,
+    #[serde(deserialize_with = "deserialize_value")]
+    pub offset: u32,
+    #[serde(deserialize_with = "deserialize_value")]
+    pub size: u32,
+    #[serde(default, deserialize_with = "deserialize_optional_mask")]
+    pub bit_mask: Option<u8>,
+}
+
+pub fn default_overrides_path() -> &'static str {
+    option_env!("SDK_OVERRIDES_PATH").unwrap_or("./sdk_overrides.toml")
+}
+
+impl SdkOverrides {
+    /// A missing file means no overrides.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let content = match fs::read_to_string(path) {
+            Ok(v) => v,
+            Err(v) if v.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(v) => return Err(format!("File: {} \n{}", path.display(), v)),
+        };
+        toml::from_str(&content).map_err(|v| format!("File: {} \n{}", path.display(), v))
+    }
+
+    /// Patches `sdk_service` and returns one line per override describing what
+    /// it changed, or that the SDK already agrees with it and it can be removed.
+    pub fn apply(&self, sdk_service: &mut SdkService) -> Vec<String> {
+        let mut report = vec![];
+
+        let mut class_names = self.classes.keys().collect::<Vec<&String>>();
+        class_names.sort();
+        for name in class_names {
+            let class_override = &self.classes[name];
+            // The SDK entry is kept: the stub only lists the members someone
+            // needed and would hide every other one.
+            if sdk_service.classes.contains_key(name) || sdk_service.structs.contains_key(name) {
+                report.push(format!(
+                    "Override {}: now exists in the SDK, the override can be removed",
+                    name
+                ));
+                continue;
+            }
+            report.push(format!("Override {}: added", name));
+
+            let attributes = class_override
+                .attributes
+                .iter()
+                .map(|v| SdkAttribute {
+                    Name: v.name.clone(),
+                    Type: v.type_.clone(),
+                    Size: v.size,
+                    Offset: v.offset,
+                    BitMask: v.bit_mask,
+                    BitField: false,
+                })
+                .collect::<Vec<SdkAttribute>>();
+            match &class_override.super_ {
+                Some(super_name) => {
+                    sdk_service.classes.insert(
+                        name.clone(),
+                        SdkClass {
+                            Super: super_name.clone(),
+                            FullSize: class_override.size,
+                            InheritedSize: 0,
+                            ClassSize: class_override.size,
+                            Attributes: attributes,
+                        },
+                    );
+                }
+                None => {
+                    sdk_service.structs.insert(
+                        name.clone(),
+                        SdkStruct {
+                            ClassSize: class_override.size,
+                            Attributes: attributes,
+                        },
+                    );
+                }
+            }
+        }
+
+        let mut member_paths = self.members.keys().collect::<Vec<&String>>();
+        member_paths.sort();
+        for attribute_path in member_paths {
+            report.push(self.apply_member(sdk_service, attribute_path));
+        }
+
+        report
+    }
+
+    fn apply_member(&self, sdk_service: &mut SdkService, attribute_path: &str) -> String {
+        let member_override = &self.members[attribute_path];
+        let (name, attribute_name) = match attribute_path.split_once('.') {
+            Some(v) => v,
+            None => {
+                return format!(
+                    "Override {}: expected at least one '.' in attribute_path",
+                    attribute_path
+                )
+            }
+        };
+        let attributes = match sdk_service.get_attributes_mut(name) {
+            Some(v) => v,
+            None => {
+                return format!(
+                    "Override {}: Class or Struct \"{}\" does not exist",
+                    attribute_path, name
+                )
+            }
+        };
+
+        let attribute = match attributes.iter_mut().find(|v| v.Name == attribute_name) {
+            Some(v) => v,
+            None => {
+                let offset = match member_override.offset {
+                    Some(v) => v,
+                    None => {
+                        return format!("Override {}: new members need an offset", attribute_path)
+                    }
+                };
+                attributes.push(SdkAttribute {
+                    Name: attribute_name.to_string(),
+                    Type: member_override.type_.clone().unwrap_or_default(),
+                    Size: member_override.size.unwrap_or(0),
+                    Offset: offset,
+                    BitMask: member_override.bit_mask,
+                    BitField: false,
+                });
+                return format!("Override {}: added at 0x{:X}", attribute_path, offset);
+            }
+        };
+
+        let agrees = member_override.offset.is_none_or(|v| v == attribute.Offset)
+            && member_override.size.is_none_or(|v| v == attribute.Size)
+            && member_override
+                .type_
+                .as_ref()
+                .is_none_or(|v| v == &attribute.Type)
+            && member_override
+                .bit_mask
+                .is_none_or(|v| Some(v) == attribute.BitMask);
+        if agrees {
+            return format!(
+                "Override {}: the SDK agrees, the override can be removed",
+                attribute_path
+            );
+        }
+
+        let mut changes = vec![];
+        if let Some(offset) = member_override.offset {
+            changes.push(format!("offset 0x{:X} -> 0x{:X}", attribute.Offset, offset));
+            attribute.Offset = offset;
+        }
+        if let Some(size) = member_override.size {
+            changes.push(format!("size 0x{:X} -> 0x{:X}", attribute.Size, size));
+            attribute.Size = size;
+        }
+        if let Some(type_) = &member_override.type_ {
+            changes.push(format!("type {} -> {}", attribute.Type, type_));
+            attribute.Type = type_.clone();
+        }
+        if let Some(bit_mask) = member_override.bit_mask {
+            match attribute.BitMask {
+                Some(v) => changes.push(format!("bit mask 0x{:X} -> 0x{:X}", v, bit_mask)),
+                None => changes.push(format!("bit mask 0x{:X}", bit_mask)),
+            }
+            attribute.BitMask = Some(bit_mask);
+        }
+        format!("Override {}: {}", attribute_path, changes.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sdk_with_crew() -> SdkService {
+        let mut sdk_service = SdkService::new();
+        sdk_service.structs.insert(
+            "Crew".to_string(),
+            SdkStruct {
+                ClassSize: 0x98,
+                Attributes: vec![SdkAttribute {
+                    Name: "Players".to_string(),
+                    Type: "TArray<PlayerState*>".to_string(),
+                    Size: 0x10,
+                    Offset: 0x20,
+                    BitMask: None,
+                    BitField: false,
+                }],
+            },
+        );
+        sdk_service
+    }
+
+    fn apply(overrides: &str, sdk_service: &mut SdkService) -> Vec<String> {
+        toml::from_str::<SdkOverrides>(overrides)
+            .unwrap()
+            .apply(sdk_service)
+    }
+
+    #[test]
+    fn patches_existing_members() {
+        let mut sdk_service = sdk_with_crew();
+        let report = apply(
+            r#"
+            [members."Crew.Players"]
+            offset = "0x28"
+            "#,
+            &mut sdk_service,
+        );
+        assert_eq!(report, vec!["Override Crew.Players: offset 0x20 -> 0x28"]);
+        assert_eq!(sdk_service.try_get_offset("Crew.Players"), Some(0x28));
+    }
+
+    #[test]
+    fn reports_overrides_the_sdk_agrees_with() {
+        let mut sdk_service = sdk_with_crew();
+        let report = apply(
+            r#"
+            [members."Crew.Players"]
+            offset = 32
+            size = "0x10"
+            "#,
+            &mut sdk_service,
+        );
+        assert_eq!(
+            report,
+            vec!["Override Crew.Players: the SDK agrees, the override can be removed"]
+        );
+    }
+
+    #[test]
+    fn adds_missing_members_and_classes() {
+        let mut sdk_service = sdk_with_crew();
+        let report = apply(
+            r#"
+            [members."Crew.bIsSunk"]
+            offset = "0x90"
+            size = 1
+            type = "bool"
+            bit_mask = "0x4"
+
+            [classes.MissingStruct]
+            size = "0x10"
+            attributes = [{ name = "Value", type = "int", offset = "0x8", size = "0x4" }]
+            "#,
+            &mut sdk_service,
+        );
+        assert_eq!(
+            report,
+            vec![
+                "Override MissingStruct: added",
+                "Override Crew.bIsSunk: added at 0x90",
+            ]
+        );
+        let attribute = sdk_service.get_attribute("Crew.bIsSunk").unwrap();
+        assert_eq!(attribute.BitMask, Some(0x4));
+        assert_eq!(sdk_service.try_get_offset("MissingStruct.Value"), Some(0x8));
+    }
+
+    #[test]
+    fn keeps_classes_that_now_exist_in_the_sdk() {
+        let mut sdk_service = sdk_with_crew();
+        let report = apply(
+            r#"
+            [classes.Crew]
+            size = "0x10"
+            attributes = [{ name = "CrewId", type = "Guid", offset = "0x0", size = "0x10" }]
+            "#,
+            &mut sdk_service,
+        );
+        assert_eq!(
+            report,
+            vec!["Override Crew: now exists in the SDK, the override can be removed"]
+        );
+        assert_eq!(sdk_service.try_get_class_or_struct_size("Crew"), Some(0x98));
+        assert_eq!(sdk_service.try_get_offset("Crew.Players"), Some(0x20));
+        assert_eq!(sdk_service.try_get_offset("Crew.CrewId"), None);
+    }
+
+    #[test]
+    fn reports_members_of_unknown_classes() {
+        let mut sdk_service = sdk_with_crew();
+        let report = apply(
+            r#"
+            [members."Ship.Crew"]
+            offset = "0x10"
+            "#,
+            &mut sdk_service,
+        );
+        assert_eq!(
+            report,
+            vec!["Override Ship.Crew: Class or Struct \"Ship\" does not exist"]
+        );
+    }
+}
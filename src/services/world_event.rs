@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::reader::ActorInfo;
+use crate::entities::world::Guid;
+use crate::services::actor_tracker::ActorEvent;
+use crate::services::game_state::{GameState, GameStateTransition};
+use crate::services::world_snapshot::{CrewSnapshot, EmissarySnapshot};
+
+/// Actors marking a world event, by FName, with the name of the event.
+const WORLD_EVENT_ACTORS: [(&str, &str); 5] = [
+    ("BP_SkellyFort_RitualSkullCloud_C", "Skeleton Fort"),
+    ("BP_LegendSkellyFort_SkullCloud_C", "Fort of Fortune"),
+    ("BP_SkellyShip_ShipCloud_C", "Skeleton Fleet"),
+    ("BP_AshenLord_SkullCloud_C", "Ashen Winds"),
+    ("BP_GhostShips_Signal_Flameheart_NetProxy_C", "Ghost Fleet"),
+];
+
+fn world_event_name(actor: &ActorInfo) -> Option<&'static str> {
+    WORLD_EVENT_ACTORS
+        .iter()
+        .find(|(raw_name, _)| actor.raw_name == *raw_name)
+        .map(|(_, name)| *name)
+}
+
+/// Actor as it appears in events, without any handle on the game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActorRef {
+    pub name: String,
+    pub address: u64,
+}
+
+impl From<&ActorInfo> for ActorRef {
+    fn from(actor: &ActorInfo) -> Self {
+        Self {
+            name: actor.raw_name.clone(),
+            address: actor.base_address.address() as u64,
+        }
+    }
+}
+
+/// Something that happened during a tick, in plain data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum WorldEvent {
+    StateChanged {
+        from: GameState,
+        to: GameState,
+    },
+    ActorSpawned {
+        actor: ActorRef,
+    },
+    ActorDespawned {
+        actor: ActorRef,
+    },
+    ActorAddressChanged {
+        old: ActorRef,
+        new: ActorRef,
+    },
+    CrewJoined {
+        crew: Guid,
+        players: u32,
+    },
+    CrewLeft {
+        crew: Guid,
+    },
+    CrewSizeChanged {
+        crew: Guid,
+        old: u32,
+        new: u32,
+    },
+    /// Factions without a loaded table count as 0 emissaries.
+    EmissaryCountChanged {
+        faction: String,
+        old: u32,
+        new: u32,
+    },
+    /// A world event such as a skeleton fleet, marked by its actor spawning.
+    EventStarted {
+        name: String,
+        actor: ActorRef,
+    },
+    EventEnded {
+        name: String,
+        actor: ActorRef,
+    },
+}
+
+impl WorldEvent {
+    /// Events for an actor event: the event itself, plus the start or end of
+    /// a world event when the actor marks one.
+    pub fn from_actor_event(event: &ActorEvent) -> Vec<WorldEvent> {
+        let mut events = vec![WorldEvent::from(event)];
+        match event {
+            ActorEvent::Spawned(actor) => {
+                if let Some(name) = world_event_name(actor) {
+                    events.push(WorldEvent::EventStarted {
+                        name: name.to_string(),
+                        actor: actor.into(),
+                    });
+                }
+            }
+            ActorEvent::Despawned(actor) => {
+                if let Some(name) = world_event_name(actor) {
+                    events.push(WorldEvent::EventEnded {
+                        name: name.to_string(),
+                        actor: actor.into(),
+                    });
+                }
+            }
+            ActorEvent::AddressChanged { .. } => {}
+        }
+        events
+    }
+}
+
+impl From<GameStateTransition> for WorldEvent {
+    fn from(transition: GameStateTransition) -> Self {
+        WorldEvent::StateChanged {
+            from: transition.from,
+            to: transition.to,
+        }
+    }
+}
+
+impl From<&ActorEvent> for WorldEvent {
+    fn from(event: &ActorEvent) -> Self {
+        match event {
+            ActorEvent::Spawned(v) => WorldEvent::ActorSpawned { actor: v.into() },
+            ActorEvent::Despawned(v) => WorldEvent::ActorDespawned { actor: v.into() },
+            ActorEvent::AddressChanged { old, new } => WorldEvent::ActorAddressChanged {
+                old: old.into(),
+                new: new.into(),
+            },
+        }
+    }
+}
+
+/// Crews that appeared, left or changed size between two ticks.
+pub fn crew_events(old: &[CrewSnapshot], new: &[CrewSnapshot]) -> Vec<WorldEvent> {
+    let old_players = old
+        .iter()
+        .map(|v| (v.id, v.players))
+        .collect::<HashMap<Guid, u32>>();
+    let new_players = new
+        .iter()
+        .map(|v| (v.id, v.players))
+        .collect::<HashMap<Guid, u32>>();
+
+    let mut events = vec![];
+    for crew in old {
+        if !new_players.contains_key(&crew.id) {
+            events.push(WorldEvent::CrewLeft { crew: crew.id });
+        }
+    }
+    for crew in new {
+        match old_players.get(&crew.id) {
+            None => events.push(WorldEvent::CrewJoined {
+                crew: crew.id,
+                players: crew.players,
+            }),
+            Some(old) if *old != crew.players => events.push(WorldEvent::CrewSizeChanged {
+                crew: crew.id,
+                old: *old,
+                new: crew.players,
+            }),
+            Some(_) => {}
+        }
+    }
+    events
+}
+
+/// Emissary counts that changed between two ticks. Factions missing from
+/// either side have no known count there, so they are not compared.
+pub fn emissary_events(old: &[EmissarySnapshot], new: &[EmissarySnapshot]) -> Vec<WorldEvent> {
+    let mut events = vec![];
+    for emissary in new {
+        let old = match old.iter().find(|v| v.faction == emissary.faction) {
+            Some(v) => v.count,
+            None => continue,
+        };
+        if old != emissary.count {
+            events.push(WorldEvent::EmissaryCountChanged {
+                faction: emissary.faction.clone(),
+                old,
+                new: emissary.count,
+            });
+        }
+    }
+    events
+}
+
+/// Hands every published event to every subscriber. Clones share the
+/// subscribers, so events can be published from the reader thread.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Vec<Sender<WorldEvent>>>>,
+}
+
+impl EventBus {
+    /// Receives every event published from now on. Dropping the receiver
+    /// unsubscribes.
+    pub fn subscribe(&self) -> Receiver<WorldEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// Drops every subscriber, whose receivers then stop waiting.
+    pub fn close(&self) {
+        self.subscribers.lock().unwrap().clear();
+    }
+
+    pub fn publish(&self, events: &[WorldEvent]) {
+        if events.is_empty() {
+            return;
+        }
+        self.subscribers.lock().unwrap().retain(|subscriber| {
+            events
+                .iter()
+                .all(|event| subscriber.send(event.clone()).is_ok())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emissaries(counts: &[(&str, u32)]) -> Vec<EmissarySnapshot> {
+        counts
+            .iter()
+            .map(|(faction, count)| EmissarySnapshot {
+                faction: faction.to_string(),
+                count: *count,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_changed_emissary_counts() {
+        let old = emissaries(&[("Reaper", 1), ("Athena", 2)]);
+        let new = emissaries(&[("Reaper", 3), ("Athena", 2)]);
+        assert_eq!(
+            emissary_events(&old, &new),
+            vec![WorldEvent::EmissaryCountChanged {
+                faction: "Reaper".to_string(),
+                old: 1,
+                new: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn ignores_factions_missing_from_one_side() {
+        let both = emissaries(&[("Reaper", 1), ("Athena", 2)]);
+        let reaper_only = emissaries(&[("Reaper", 1)]);
+        assert!(emissary_events(&both, &reaper_only).is_empty());
+        assert!(emissary_events(&reaper_only, &both).is_empty());
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+use crate::core::context::ReaderContext;
+use crate::core::reader::{ActorInfo, ActorKey, MemoryReaderError, ProcessTarget};
+use crate::entities::actor::Actor;
+use crate::entities::world::{CrewService, World};
+use crate::services::actor_tracker::{ActorEvent, ActorTracker};
+use crate::services::game_state::{GameObservation, GameSession, GameState};
+use crate::services::process_watcher::ProcessWatcher;
+use crate::services::sdk::SdkHandle;
+use crate::services::world_event::{crew_events, emissary_events, EventBus, WorldEvent};
+use crate::services::world_snapshot::{
+    now_millis, ActorSnapshot, CrewSnapshot, EmissarySnapshot, WorldSnapshot,
+};
+use crate::structs::unreal::FVector;
+
+/// Listing the processes is too slow to run each tick.
+const PROCESS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How old an actor position gets before it is read again.
+const POSITION_REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+/// A world has thousands of actors, too many to read every position each tick.
+const MAX_POSITION_READS_PER_TICK: usize = 256;
+
+/// Actor of the current world, with what does not change over its lifetime.
+struct TrackedActor {
+    actor: Actor,
+    class: Option<String>,
+    position: Option<FVector>,
+    position_read_at: Option<Instant>,
+}
+
+impl TrackedActor {
+    fn is_position_stale(&self, now: Instant) -> bool {
+        self.position_read_at
+            .is_none_or(|v| now.duration_since(v) >= POSITION_REFRESH_INTERVAL)
+    }
+}
+
+/// Prints read errors when they change, rather than on every tick they keep
+/// failing.
+#[derive(Default)]
+struct ErrorLog {
+    last: HashMap<String, String>,
+}
+
+impl ErrorLog {
+    fn check<T>(&mut self, what: &str, result: Result<T, MemoryReaderError>) -> Option<T> {
+        match result {
+            Ok(v) => {
+                self.last.remove(what);
+                Some(v)
+            }
+            Err(error) => {
+                let message = format!("{:?}", error);
+                if self.last.get(what) != Some(&message) {
+                    eprintln!("Could not read {}: {}", what, message);
+                    self.last.insert(what.to_string(), message);
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Reads the game and turns each read into a `WorldSnapshot`.
+pub struct WorldReader {
+    process_watcher: ProcessWatcher,
+    actor_tracker: ActorTracker,
+    actors: HashMap<ActorKey, TrackedActor>,
+    session: GameSession,
+    world: Option<World>,
+    tick: u64,
+    crews: Vec<CrewSnapshot>,
+    emissaries: Vec<EmissarySnapshot>,
+    events: EventBus,
+    errors: ErrorLog,
+}
+
+impl WorldReader {
+    pub fn new(target: ProcessTarget, sdk: SdkHandle, max_array_count: u32) -> Self {
+        Self {
+            process_watcher: ProcessWatcher::new(
+                target,
+                sdk,
+                max_array_count,
+                PROCESS_POLL_INTERVAL,
+            ),
+            actor_tracker: ActorTracker::new(),
+            actors: HashMap::new(),
+            session: GameSession::new(),
+            world: None,
+            tick: 0,
+            crews: vec![],
+            emissaries: vec![],
+            events: EventBus::default(),
+            errors: ErrorLog::default(),
+        }
+    }
+
+    /// See `ProcessWatcher::prefer_pid`.
+    pub fn prefer_pid(&mut self, pid: Option<u32>) {
+        self.process_watcher.prefer_pid(pid);
+    }
+
+    /// Receives the events of every tick from now on, see `WorldEvent`.
+    ///
+    /// ## Example:
+    /// ```
+    /// let events = world_reader.subscribe();
+    /// let reader_thread = ReaderThread::spawn(world_reader, 30.0);
+    /// for event in events.iter() {
+    ///     if let WorldEvent::CrewJoined { players, .. } = event {
+    ///         println!("A crew of {} joined", players);
+    ///     }
+    /// }
+    /// ```
+    pub fn subscribe(&self) -> Receiver<WorldEvent> {
+        self.events.subscribe()
+    }
+
+    /// Shares the subscribers, so that they can still be disconnected once the
+    /// reader has been moved to another thread.
+    pub fn event_bus(&self) -> EventBus {
+        self.events.clone()
+    }
+
+    fn observe(&mut self) -> (GameObservation, Vec<ActorEvent>) {
+        self.process_watcher.poll();
+        let process_running = self.process_watcher.is_process_running();
+        let reader = match self.process_watcher.reader() {
+            Some(v) => v,
+            None => {
+                let events = self.actor_tracker.update(HashMap::new());
+                let observation = GameObservation {
+                    process_running,
+                    ..Default::default()
+                };
+                return (observation, events);
+            }
+        };
+        let mut observation = GameObservation {
+            process_running,
+            attached: true,
+            ..Default::default()
+        };
+
+        // A failed read says nothing about the world, so the actors and levels
+        // of the last successful one are kept until the next.
+        let events = match self.errors.check("actors", reader.read_actors()) {
+            Some(actors) => self.actor_tracker.update(actors),
+            None => vec![],
+        };
+        observation.world_loaded = !reader.levels().is_empty();
+        observation.level_names = reader.levels().iter().map(|v| v.name.clone()).collect();
+        observation.has_crew_service = self
+            .actor_tracker
+            .find_by_name("CrewService")
+            .next()
+            .is_some();
+        (observation, events)
+    }
+
+    /// Applies the actor events to `actors`.
+    fn update_actors(&mut self, events: &[ActorEvent]) {
+        let ctx = match self.process_watcher.reader() {
+            Some(v) => v.ctx(),
+            None => return self.actors.clear(),
+        };
+        let track = |info: &ActorInfo| TrackedActor {
+            actor: Actor::new(ctx, info.clone()),
+            class: ctx.read_class_name(info.base_address).ok(),
+            position: None,
+            position_read_at: None,
+        };
+        for event in events {
+            match event {
+                ActorEvent::Spawned(v) => {
+                    self.actors.insert(v.key(), track(v));
+                }
+                ActorEvent::Despawned(v) => {
+                    self.actors.remove(&v.key());
+                }
+                ActorEvent::AddressChanged { old, new } => {
+                    self.actors.remove(&old.key());
+                    self.actors.insert(new.key(), track(new));
+                }
+            }
+        }
+    }
+
+    /// Keeps `world` in sync with the current `CrewService` actor.
+    fn update_world(&mut self) {
+        let ctx = match self.process_watcher.reader() {
+            Some(v) => v.ctx(),
+            None => return self.world = None,
+        };
+        let actor_info = match self.actor_tracker.find_by_name("CrewService").next() {
+            Some(v) => v,
+            None => return self.world = None,
+        };
+        let is_current = self
+            .world
+            .as_ref()
+            .and_then(|v| v.crew_service.as_ref())
+            .is_some_and(|v| v.actor().key() == actor_info.key());
+        if !is_current {
+            let mut world = World::new();
+            world.crew_service = Some(CrewService::new(ctx, actor_info.clone()));
+            self.world = Some(world);
+        }
+
+        let world = self.world.as_mut().unwrap();
+        world
+            .emissary_tables
+            .update_actors(self.actor_tracker.actors());
+        self.errors.check("crews", world.update(ctx));
+    }
+
+    fn snapshot(&mut self, levels: Vec<String>, events: Vec<WorldEvent>) -> WorldSnapshot {
+        let mut snapshot = WorldSnapshot {
+            tick: self.tick,
+            timestamp: now_millis(),
+            state: self.session.state(),
+            levels,
+            events,
+            ..WorldSnapshot::empty()
+        };
+        let ctx: &ReaderContext = match self.process_watcher.reader() {
+            Some(v) => v.ctx(),
+            None => return snapshot,
+        };
+
+        let now = Instant::now();
+        let mut position_reads = 0;
+        for tracked in self.actors.values_mut() {
+            if position_reads < MAX_POSITION_READS_PER_TICK && tracked.is_position_stale(now) {
+                tracked.position = tracked.actor.get_coordinates(ctx).ok();
+                tracked.position_read_at = Some(now);
+                position_reads += 1;
+            }
+        }
+        snapshot.actors = self
+            .actors
+            .values()
+            .map(|v| ActorSnapshot {
+                name: v.actor.info.raw_name.clone(),
+                class: v.class.clone(),
+                address: v.actor.info.base_address.address() as u64,
+                position: v.position,
+            })
+            .collect();
+
+        let world = match self.world.as_ref() {
+            Some(v) => v,
+            None => return snapshot,
+        };
+        if let Some(crew_service) = world.crew_service.as_ref() {
+            snapshot.crews = crew_service
+                .crews()
+                .iter()
+                .map(|(id, players)| CrewSnapshot {
+                    id: *id,
+                    players: *players,
+                })
+                .collect();
+            snapshot.total_players = crew_service.total_players();
+        }
+        for (faction, count) in world.emissary_tables.read_counts(ctx) {
+            let what = format!("{} emissaries", faction);
+            // A failed read keeps the last known count rather than dropping
+            // the faction until the next good one.
+            let count = self.errors.check(&what, count).or_else(|| {
+                self.emissaries
+                    .iter()
+                    .find(|v| v.faction == faction)
+                    .map(|v| v.count)
+            });
+            if let Some(count) = count {
+                snapshot.emissaries.push(EmissarySnapshot {
+                    faction: faction.to_string(),
+                    count,
+                });
+            }
+        }
+        snapshot
+    }
+
+    /// Reads the game once and publishes what changed since the last tick.
+    pub fn tick(&mut self) -> WorldSnapshot {
+        self.tick += 1;
+        let (observation, actor_events) = self.observe();
+        let mut events = vec![];
+        if let Some(transition) = self.session.update(&observation) {
+            events.push(WorldEvent::from(transition));
+        }
+        events.extend(actor_events.iter().flat_map(WorldEvent::from_actor_event));
+        self.update_actors(&actor_events);
+
+        match self.session.state() {
+            GameState::InGame => self.update_world(),
+            _ => self.world = None,
+        }
+        let mut snapshot = self.snapshot(observation.level_names, events);
+
+        snapshot
+            .events
+            .extend(crew_events(&self.crews, &snapshot.crews));
+        snapshot
+            .events
+            .extend(emissary_events(&self.emissaries, &snapshot.emissaries));
+        self.crews = snapshot.crews.clone();
+        self.emissaries = snapshot.emissaries.clone();
+
+        self.events.publish(&snapshot.events);
+        snapshot
+    }
+}
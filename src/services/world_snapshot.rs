@@ -0,0 +1,94 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::world::Guid;
+use crate::services::game_state::GameState;
+use crate::services::world_event::WorldEvent;
+use crate::structs::unreal::FVector;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrewSnapshot {
+    pub id: Guid,
+    pub players: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmissarySnapshot {
+    pub faction: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorSnapshot {
+    pub name: String,
+    /// Runtime class, `None` when it could not be read.
+    pub class: Option<String>,
+    pub address: u64,
+    /// `None` for actors without a root component.
+    pub position: Option<FVector>,
+}
+
+/// Everything the reader saw on one tick, in plain data. Published as a
+/// whole and never modified afterwards, so the GUI, the CLI and exports can
+/// all consume it without access to the game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    /// Number of the tick that produced it, starting at 1.
+    pub tick: u64,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub state: GameState,
+    pub levels: Vec<String>,
+    pub crews: Vec<CrewSnapshot>,
+    pub total_players: u32,
+    pub emissaries: Vec<EmissarySnapshot>,
+    pub actors: Vec<ActorSnapshot>,
+    /// What changed since the previous tick.
+    pub events: Vec<WorldEvent>,
+}
+
+impl WorldSnapshot {
+    /// Snapshot published before the first tick.
+    pub fn empty() -> Self {
+        Self {
+            tick: 0,
+            timestamp: now_millis(),
+            state: GameState::ProcessNotRunning,
+            levels: vec![],
+            crews: vec![],
+            total_players: 0,
+            emissaries: vec![],
+            actors: vec![],
+            events: vec![],
+        }
+    }
+
+    pub fn status_lines(&self) -> Vec<String> {
+        let mut lines = vec![self.state.to_string()];
+        if self.state != GameState::InGame {
+            return lines;
+        }
+        lines.push(String::new());
+        for (index, crew) in self.crews.iter().enumerate() {
+            lines.push(format!("Crew {}: {} players", index, crew.players));
+        }
+        lines.push(format!("Total players: {}", self.total_players));
+        if !self.emissaries.is_empty() {
+            lines.push(String::new());
+        }
+        for emissary in &self.emissaries {
+            lines.push(format!(
+                "{}: {} emissaries",
+                emissary.faction, emissary.count
+            ));
+        }
+        lines
+    }
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |v| v.as_millis() as u64)
+}
@@ -1,3 +1,7 @@
 pub mod TArray;
+pub mod pod;
+pub mod remote_ptr;
+pub mod remote_struct;
 pub mod tarray;
+pub mod tset;
 pub mod unreal;
@@ -0,0 +1,34 @@
+use std::{mem::size_of, ptr};
+
+/// Plain-old-data types that can be read out of the game memory.
+///
+/// # Safety
+/// Implementors must be `Copy`, hold no references or owned heap data, and be
+/// valid for every bit pattern (all zeroes included).
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(u8, i8, u16, i16, u32, i32, u64, i64, usize, isize, f32, f64);
+
+unsafe impl<T: 'static> Pod for *mut T {}
+unsafe impl<T: 'static> Pod for *const T {}
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// Reads a `T` from the start of `bytes`, which need not be aligned.
+///
+/// Panics if `bytes` is shorter than `T`.
+pub fn read_unaligned<T: Pod>(bytes: &[u8]) -> T {
+    assert!(
+        bytes.len() >= size_of::<T>(),
+        "{} bytes are too few for a {}-byte value",
+        bytes.len(),
+        size_of::<T>()
+    );
+    // Sound because `T: Pod` is valid for any bit pattern.
+    unsafe { ptr::read_unaligned(bytes.as_ptr() as *const T) }
+}
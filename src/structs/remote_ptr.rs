@@ -0,0 +1,125 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use crate::{
+    core::{
+        context::ReaderContext,
+        reader::{MemoryReaderError, MemorySource, MAX_USER_ADDRESS},
+    },
+    structs::{pod::Pod, remote_struct::RemoteStruct},
+};
+
+/// Address of a `T` in the game memory. Same layout as a raw 64-bit pointer,
+/// so it can be read as a member of remote structs.
+#[repr(transparent)]
+pub struct RemotePtr<T> {
+    address: u64,
+    /// Not `*const T`, so that remote pointers can be sent to other threads.
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> RemotePtr<T> {
+    pub fn new(address: usize) -> Self {
+        Self {
+            address: address as u64,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn null() -> Self {
+        Self::new(0)
+    }
+
+    pub fn address(self) -> usize {
+        self.address as usize
+    }
+
+    pub fn is_null(self) -> bool {
+        self.address == 0
+    }
+
+    /// Non-null and within the user-mode address range.
+    pub fn is_valid(self) -> bool {
+        self.address != 0 && self.address <= MAX_USER_ADDRESS
+    }
+
+    /// Same pointer moved by `bytes`.
+    pub fn offset(self, bytes: usize) -> Self {
+        Self::new(self.address() + bytes)
+    }
+
+    pub fn cast<U>(self) -> RemotePtr<U> {
+        RemotePtr::new(self.address())
+    }
+
+    /// Pointer to a member of type `U` at `offset` bytes into the pointee.
+    pub fn field<U>(self, offset: u32) -> RemotePtr<U> {
+        RemotePtr::new(self.address() + offset as usize)
+    }
+
+    pub fn read<S: MemorySource>(self, source: &S) -> Result<T, MemoryReaderError>
+    where
+        T: Pod,
+    {
+        if !self.is_valid() {
+            return Err(MemoryReaderError::MemoryReadingError(format!(
+                "Invalid pointer {:?}",
+                self
+            )));
+        }
+        source.read(self.address())
+    }
+
+    /// Reads a `#[derive(RemoteStruct)]` view of the pointee.
+    pub fn read_struct(self, ctx: &ReaderContext) -> Result<T, MemoryReaderError>
+    where
+        T: RemoteStruct,
+    {
+        if !self.is_valid() {
+            return Err(MemoryReaderError::MemoryReadingError(format!(
+                "Invalid pointer {:?}",
+                self
+            )));
+        }
+        T::read_from(ctx, self.address())
+    }
+}
+
+impl<T> Clone for RemotePtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RemotePtr<T> {}
+
+impl<T> PartialEq for RemotePtr<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.address == other.address
+    }
+}
+
+impl<T> Eq for RemotePtr<T> {}
+
+impl<T> Hash for RemotePtr<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+    }
+}
+
+impl<T> Default for RemotePtr<T> {
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+impl<T> fmt::Debug for RemotePtr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RemotePtr(0x{:X})", self.address)
+    }
+}
+
+unsafe impl<T: 'static> Pod for RemotePtr<T> {}
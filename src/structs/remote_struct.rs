@@ -0,0 +1,117 @@
+use std::{
+    mem::size_of,
+    sync::{Arc, RwLock},
+};
+
+use crate::{
+    core::{
+        context::ReaderContext,
+        reader::{MemoryReaderError, MemorySource},
+    },
+    services::sdk::SdkHandle,
+    structs::pod::{read_unaligned, Pod},
+};
+
+pub use sot_framework_derive::RemoteStruct;
+
+/// Where the fields of a `RemoteStruct` live, relative to the block read.
+pub struct RemoteLayout {
+    generation: u64,
+    start: usize,
+    size: usize,
+    offsets: Vec<usize>,
+}
+
+impl RemoteLayout {
+    fn resolve(
+        sdk_handle: &SdkHandle,
+        paths: &[&'static str],
+        sizes: &[usize],
+    ) -> Result<Self, MemoryReaderError> {
+        let generation = sdk_handle.generation();
+        let sdk = sdk_handle.get();
+
+        let mut offsets = vec![];
+        for (path, size) in paths.iter().zip(sizes) {
+            let attribute = sdk.get_attribute(path).ok_or_else(|| {
+                MemoryReaderError::UnknownType(format!(
+                    "Class or Struct attribute \"{}\" does not exist",
+                    path
+                ))
+            })?;
+            if *size > attribute.Size as usize {
+                return Err(MemoryReaderError::UnknownType(format!(
+                    "{} is 0x{:X} bytes in the SDK but the field is 0x{:X} bytes",
+                    path, attribute.Size, size
+                )));
+            }
+            offsets.push(attribute.Offset as usize);
+        }
+
+        let start = offsets.iter().copied().min().unwrap_or(0);
+        let end = offsets
+            .iter()
+            .zip(sizes)
+            .map(|(offset, size)| offset + size)
+            .max()
+            .unwrap_or(0);
+        Ok(Self {
+            generation,
+            start,
+            size: end - start,
+            offsets: offsets.iter().map(|v| v - start).collect(),
+        })
+    }
+
+    pub fn field<T: Pod>(&self, block: &[u8], index: usize) -> T {
+        let offset = self.offsets[index];
+        let bytes = &block[offset..offset + size_of::<T>()];
+        read_unaligned(bytes)
+    }
+}
+
+/// Per-type cache of the layout, resolved on first use and again whenever the
+/// SDK it was resolved with is reloaded or another one is used.
+pub struct RemoteLayoutCache {
+    paths: &'static [&'static str],
+    sizes: &'static [usize],
+    layout: RwLock<Option<Arc<RemoteLayout>>>,
+}
+
+impl RemoteLayoutCache {
+    pub const fn new(paths: &'static [&'static str], sizes: &'static [usize]) -> Self {
+        Self {
+            paths,
+            sizes,
+            layout: RwLock::new(None),
+        }
+    }
+
+    pub fn get(&self, sdk: &SdkHandle) -> Result<Arc<RemoteLayout>, MemoryReaderError> {
+        if let Some(layout) = self.layout.read().unwrap().as_ref() {
+            if layout.generation == sdk.generation() {
+                return Ok(layout.clone());
+            }
+        }
+        let layout = Arc::new(RemoteLayout::resolve(sdk, self.paths, self.sizes)?);
+        *self.layout.write().unwrap() = Some(layout.clone());
+        Ok(layout)
+    }
+}
+
+/// Local view of some members of a remote class, implemented with
+/// `#[derive(RemoteStruct)]`.
+pub trait RemoteStruct: Sized {
+    /// Fails when a member is missing from the SDK or smaller than its field.
+    fn layout(sdk: &SdkHandle) -> Result<Arc<RemoteLayout>, MemoryReaderError>;
+
+    fn from_block(layout: &RemoteLayout, block: &[u8]) -> Self;
+
+    /// Reads every field with a single read, from the first to the last one.
+    fn read_from(ctx: &ReaderContext, address: usize) -> Result<Self, MemoryReaderError> {
+        let layout = Self::layout(ctx.sdk())?;
+        let mut block = vec![0; layout.size];
+        ctx.read_into(address + layout.start, &mut block)?;
+        Ok(Self::from_block(&layout, &block))
+    }
+}
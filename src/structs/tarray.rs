@@ -1,60 +1,69 @@
-use std::{
-    mem::{self, size_of},
-    os::raw::c_void,
-    slice,
+use std::{iter::Copied, mem::size_of, ops::Deref, os::raw::c_void, slice};
+
+use crate::structs::{
+    pod::{read_unaligned, Pod},
+    remote_ptr::RemotePtr,
 };
 
-pub struct TArray<T> {
-    item_size: usize,
-    raw_bytes: Vec<u8>,
+/// In-memory layout of an Unreal `TArray`.
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+pub struct TArrayHeader {
+    pub data: u64,
     pub count: u32,
-    _marker: std::marker::PhantomData<T>,
+    pub max: u32,
 }
 
-pub struct TArrayIter<'a, T> {
-    array: &'a TArray<T>,
-    index: u32,
-    _marker: std::marker::PhantomData<T>,
+unsafe impl Pod for TArrayHeader {}
+
+/// Local copy of the elements of a remote `TArray`.
+pub struct TArray<T: Pod> {
+    items: Vec<T>,
 }
 
-impl<T> TArray<T> {
+impl<T: Pod> TArray<T> {
     pub fn new(raw_bytes: Vec<u8>, count: u32) -> Self {
-        Self {
-            item_size: size_of::<T>(),
-            raw_bytes,
-            count,
-            _marker: std::marker::PhantomData,
-        }
+        let items = raw_bytes
+            .chunks_exact(size_of::<T>())
+            .take(count as usize)
+            .map(read_unaligned::<T>)
+            .collect();
+        Self { items }
     }
 
-    pub fn iter(&self) -> TArrayIter<T> {
-        TArrayIter {
-            array: self,
-            index: 0,
-            _marker: std::marker::PhantomData,
-        }
+    pub fn len(&self) -> usize {
+        self.items.len()
     }
-}
 
-impl<'a, T> Iterator for TArrayIter<'a, T> {
-    type Item = T;
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.index >= self.array.count {
-            return None;
-        }
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.items.get(index).copied()
+    }
 
-        let buffer = &self.array.raw_bytes[(self.index as usize * self.array.item_size)
-            ..((self.index as usize + 1) * self.array.item_size)];
-        let mut item: T = unsafe { mem::zeroed() };
-        unsafe {
-            let item_slice =
-                slice::from_raw_parts_mut(&mut item as *mut _ as *mut u8, self.array.item_size);
-            item_slice.copy_from_slice(buffer);
-        }
+    pub fn iter(&self) -> Copied<slice::Iter<'_, T>> {
+        self.items.iter().copied()
+    }
 
-        self.index += 1;
-        Some(item)
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+}
+
+/// Gives indexing (`array[0]`) and slicing (`&array[1..3]`).
+impl<T: Pod> Deref for TArray<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.items
+    }
+}
+
+impl<T: Pod> From<TArray<T>> for Vec<T> {
+    fn from(array: TArray<T>) -> Self {
+        array.items
     }
 }
 
@@ -73,7 +82,7 @@ impl TArrayStruct {
         }
     }
 
-    pub fn iter(&self) -> TArrayStructIter {
+    pub fn iter(&self) -> TArrayStructIter<'_> {
         TArrayStructIter {
             array: self,
             index: 0,
@@ -82,7 +91,7 @@ impl TArrayStruct {
 }
 
 pub struct TArrayStructItem {
-    pub item_pointer: *mut c_void,
+    pub item_pointer: RemotePtr<c_void>,
 }
 
 pub struct TArrayStructIter<'a> {
@@ -99,7 +108,7 @@ impl Iterator for TArrayStructIter<'_> {
         }
 
         let pointer =
-            (self.array.array_pointer + self.index as usize * self.array.item_size) as *mut c_void;
+            RemotePtr::new(self.array.array_pointer + self.index as usize * self.array.item_size);
 
         self.index += 1;
         Some(TArrayStructItem {
@@ -0,0 +1,276 @@
+use std::{
+    iter::Copied,
+    mem::{align_of, size_of},
+    ops::Deref,
+    os::raw::c_void,
+    slice,
+};
+
+use crate::structs::{
+    pod::{read_unaligned, Pod},
+    remote_ptr::RemotePtr,
+    tarray::{TArrayHeader, TArrayStructItem},
+};
+
+/// In-memory layout of `TBitArray<FDefaultBitArrayAllocator>`. Up to 128 bits
+/// are stored inline, larger arrays live behind `secondary_data`.
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+pub struct TBitArrayHeader {
+    pub inline_data: [u32; 4],
+    pub secondary_data: u64,
+    pub num_bits: i32,
+    pub max_bits: i32,
+}
+
+unsafe impl Pod for TBitArrayHeader {}
+
+/// In-memory layout of `TSparseArray`, the element storage of `TSet` and `TMap`.
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+pub struct TSparseArrayHeader {
+    pub data: TArrayHeader,
+    pub allocation_flags: TBitArrayHeader,
+    pub first_free_index: i32,
+    pub num_free_indices: i32,
+}
+
+unsafe impl Pod for TSparseArrayHeader {}
+
+fn align_up(value: usize, align: usize) -> usize {
+    value.div_ceil(align) * align
+}
+
+/// Size of a `TSetElement` holding a value of the given size and alignment:
+/// the value followed by `HashNextId` and `HashIndex`.
+pub fn set_element_size(value_size: usize, value_align: usize) -> usize {
+    let align = value_align.max(align_of::<i32>());
+    align_up(align_up(value_size, align_of::<i32>()) + 8, align)
+}
+
+/// Offset of the value inside a `TPair<K, V>`.
+pub fn pair_value_offset(key_size: usize, value_align: usize) -> usize {
+    align_up(key_size, value_align)
+}
+
+/// Allocation flags of a sparse array, one bit per slot.
+pub struct AllocationFlags {
+    words: Vec<u32>,
+}
+
+impl AllocationFlags {
+    pub fn new(words: Vec<u32>) -> Self {
+        Self { words }
+    }
+
+    pub fn is_allocated(&self, index: usize) -> bool {
+        match self.words.get(index / 32) {
+            Some(word) => word & (1 << (index % 32)) != 0,
+            None => false,
+        }
+    }
+}
+
+/// Local copy of the allocated elements of a remote `TSet`.
+pub struct TSet<T: Pod> {
+    items: Vec<T>,
+}
+
+impl<T: Pod> TSet<T> {
+    pub fn element_size() -> usize {
+        set_element_size(size_of::<T>(), align_of::<T>())
+    }
+
+    /// `raw_bytes` holds every slot of the sparse array, allocated or not.
+    pub fn new(raw_bytes: Vec<u8>, flags: &AllocationFlags) -> Self {
+        let items = raw_bytes
+            .chunks_exact(Self::element_size())
+            .enumerate()
+            .filter(|(index, _)| flags.is_allocated(*index))
+            .map(|(_, chunk)| read_unaligned::<T>(chunk))
+            .collect();
+        Self { items }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> Copied<slice::Iter<'_, T>> {
+        self.items.iter().copied()
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+}
+
+impl<T: Pod> Deref for TSet<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.items
+    }
+}
+
+/// Local copy of the allocated key/value pairs of a remote `TMap`.
+pub struct TMap<K: Pod, V: Pod> {
+    pairs: Vec<(K, V)>,
+}
+
+impl<K: Pod, V: Pod> TMap<K, V> {
+    pub fn value_offset() -> usize {
+        pair_value_offset(size_of::<K>(), align_of::<V>())
+    }
+
+    pub fn element_size() -> usize {
+        let pair_align = align_of::<K>().max(align_of::<V>());
+        let pair_size = align_up(Self::value_offset() + size_of::<V>(), pair_align);
+        set_element_size(pair_size, pair_align)
+    }
+
+    /// `raw_bytes` holds every slot of the sparse array, allocated or not.
+    pub fn new(raw_bytes: Vec<u8>, flags: &AllocationFlags) -> Self {
+        let value_offset = Self::value_offset();
+        let pairs = raw_bytes
+            .chunks_exact(Self::element_size())
+            .enumerate()
+            .filter(|(index, _)| flags.is_allocated(*index))
+            .map(|(_, chunk)| {
+                (
+                    read_unaligned::<K>(chunk),
+                    read_unaligned::<V>(&chunk[value_offset..]),
+                )
+            })
+            .collect();
+        Self { pairs }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        K: PartialEq,
+    {
+        self.pairs.iter().find(|(k, _)| k == key).map(|(_, v)| *v)
+    }
+
+    pub fn iter(&self) -> Copied<slice::Iter<'_, (K, V)>> {
+        self.pairs.iter().copied()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.pairs.iter().map(|(k, _)| *k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = V> + '_ {
+        self.pairs.iter().map(|(_, v)| *v)
+    }
+
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.pairs
+    }
+}
+
+/// Remote `TSet` of a blueprint type, whose element size comes from the SDK.
+pub struct TSetStruct {
+    data_pointer: usize,
+    element_size: usize,
+    count: u32,
+    flags: AllocationFlags,
+}
+
+impl TSetStruct {
+    pub fn new(
+        data_pointer: usize,
+        element_size: usize,
+        count: u32,
+        flags: AllocationFlags,
+    ) -> Self {
+        Self {
+            data_pointer,
+            element_size,
+            count,
+            flags,
+        }
+    }
+
+    /// Yields a pointer to each allocated element.
+    pub fn iter(&self) -> impl Iterator<Item = TArrayStructItem> + '_ {
+        (0..self.count as usize)
+            .filter(|index| self.flags.is_allocated(*index))
+            .map(|index| TArrayStructItem {
+                item_pointer: RemotePtr::new(self.data_pointer + index * self.element_size),
+            })
+    }
+}
+
+pub struct TMapStructItem {
+    pub key_pointer: RemotePtr<c_void>,
+    pub value_pointer: RemotePtr<c_void>,
+}
+
+/// Remote `TMap` of blueprint types. `value_offset` is the offset of the value
+/// inside the `TPair`, see `pair_value_offset`.
+pub struct TMapStruct {
+    set: TSetStruct,
+    value_offset: usize,
+}
+
+impl TMapStruct {
+    pub fn new(set: TSetStruct, value_offset: usize) -> Self {
+        Self { set, value_offset }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = TMapStructItem> + '_ {
+        self.set.iter().map(|item| TMapStructItem {
+            key_pointer: item.item_pointer,
+            value_pointer: item.item_pointer.offset(self.value_offset),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_elements_end_with_the_hash_ids() {
+        assert_eq!(set_element_size(4, 4), 12);
+        assert_eq!(set_element_size(1, 1), 12);
+        assert_eq!(set_element_size(8, 8), 16);
+        assert_eq!(set_element_size(12, 8), 24);
+        assert_eq!(TSet::<u64>::element_size(), 16);
+    }
+
+    #[test]
+    fn pair_values_are_aligned_after_the_key() {
+        assert_eq!(pair_value_offset(4, 4), 4);
+        assert_eq!(pair_value_offset(4, 8), 8);
+        assert_eq!(pair_value_offset(3, 2), 4);
+        assert_eq!(TMap::<u32, u64>::value_offset(), 8);
+        assert_eq!(TMap::<u32, u64>::element_size(), 24);
+        assert_eq!(TMap::<u8, u32>::element_size(), 16);
+    }
+
+    #[test]
+    fn allocation_flags_past_the_words_are_free() {
+        let flags = AllocationFlags::new(vec![0b101, 1]);
+        assert!(flags.is_allocated(0));
+        assert!(!flags.is_allocated(1));
+        assert!(flags.is_allocated(2));
+        assert!(flags.is_allocated(32));
+        assert!(!flags.is_allocated(33));
+        assert!(!flags.is_allocated(64));
+    }
+}
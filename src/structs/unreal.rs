@@ -1,36 +1,154 @@
 use std::ffi::c_void;
 
+use serde::{Deserialize, Serialize};
+
+use crate::structs::{pod::Pod, remote_ptr::RemotePtr};
+
 pub type ULONG_PTR = usize;
 
 #[repr(C, packed)]
+#[derive(Clone, Copy)]
 pub struct FName {
     pub index: u32,
     pub unknown_data_00: u32,
 }
 
+unsafe impl Pod for FName {}
+
 #[repr(C, packed)]
+#[derive(Clone, Copy)]
 pub struct UObject {
     pub v_table_object: *mut c_void,
     pub object_flag: u32,
     pub internal_index: u32,
-    pub u_class: *mut UClass,
+    pub u_class: RemotePtr<UClass>,
     pub name: FName,
-    pub outer: *mut Self,
+    pub outer: RemotePtr<Self>,
 }
 
+unsafe impl Pod for UObject {}
+
 #[repr(C, packed)]
+#[derive(Clone, Copy)]
 pub struct UClass {
     pub v_table_object: u64,
     pub object_flag: u32,
     pub internal_index: u32,
-    pub u_class: *const Self,
+    pub u_class: RemotePtr<Self>,
     pub name: FName,
-    pub outer: *const UObject,
+    pub outer: RemotePtr<UObject>,
 }
 
+unsafe impl Pod for UClass {}
+
 #[repr(C, packed)]
 pub struct Actor {
     ptr: u64,
     id: u32,
     name: String,
 }
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FVector {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+unsafe impl Pod for FVector {}
+
+impl FVector {
+    pub fn length(&self) -> f32 {
+        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+    }
+
+    pub fn distance(&self, other: &FVector) -> f32 {
+        FVector {
+            x: self.x - other.x,
+            y: self.y - other.y,
+            z: self.z - other.z,
+        }
+        .length()
+    }
+}
+
+/// Rotation in degrees.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FRotator {
+    pub pitch: f32,
+    pub yaw: f32,
+    pub roll: f32,
+}
+
+unsafe impl Pod for FRotator {}
+
+fn normalize_axis(angle: f32) -> f32 {
+    let angle = angle % 360.0;
+    if angle > 180.0 {
+        angle - 360.0
+    } else if angle < -180.0 {
+        angle + 360.0
+    } else {
+        angle
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FQuat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+unsafe impl Pod for FQuat {}
+
+impl FQuat {
+    /// Same conversion as `FQuat::Rotator` in the engine.
+    pub fn to_rotator(self) -> FRotator {
+        const SINGULARITY_THRESHOLD: f32 = 0.4999995;
+
+        let singularity_test = self.z * self.x - self.w * self.y;
+        let yaw_y = 2.0 * (self.w * self.z + self.x * self.y);
+        let yaw_x = 1.0 - 2.0 * (self.y * self.y + self.z * self.z);
+        let yaw = yaw_y.atan2(yaw_x).to_degrees();
+
+        if singularity_test < -SINGULARITY_THRESHOLD {
+            FRotator {
+                pitch: -90.0,
+                yaw,
+                roll: normalize_axis(-yaw - 2.0 * self.x.atan2(self.w).to_degrees()),
+            }
+        } else if singularity_test > SINGULARITY_THRESHOLD {
+            FRotator {
+                pitch: 90.0,
+                yaw,
+                roll: normalize_axis(yaw - 2.0 * self.x.atan2(self.w).to_degrees()),
+            }
+        } else {
+            FRotator {
+                pitch: (2.0 * singularity_test).asin().to_degrees(),
+                yaw,
+                roll: (-2.0 * (self.w * self.x + self.y * self.z))
+                    .atan2(1.0 - 2.0 * (self.x * self.x + self.y * self.y))
+                    .to_degrees(),
+            }
+        }
+    }
+}
+
+/// Vectors are padded to 16 bytes inside a transform.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FTransform {
+    pub rotation: FQuat,
+    pub translation: FVector,
+    _pad_0x1c: u32,
+    pub scale_3d: FVector,
+    _pad_0x2c: u32,
+}
+
+unsafe impl Pod for FTransform {}